@@ -0,0 +1,106 @@
+use bdk::bitcoin::secp256k1::Secp256k1;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use extended_keys_derive_rust::{
+    DerivationCache, DerivationPath, DescriptorKey, DescriptorSecretKey, Mnemonic, Network,
+};
+
+const MNEMONIC: &str = "chaos fabric time speed sponsor all flat solution wisdom trophy crack object robot pave observe combine where aware bench orient secret primary cable detect";
+const KEYS: u32 = 1000;
+
+fn master() -> DescriptorSecretKey {
+    let mnemonic = Mnemonic::from_string(MNEMONIC.to_string()).unwrap();
+    DescriptorSecretKey::new(Network::Testnet, &mnemonic, None).unwrap()
+}
+
+fn path(path: &str) -> DerivationPath {
+    DerivationPath::new(path.to_string()).unwrap()
+}
+
+fn bulk_derivation(c: &mut Criterion) {
+    let master = master();
+    let account = master.derive(&path("m/84h/1h/0h")).unwrap();
+    let receive = account.extend(&path("m/0")).unwrap();
+    let account_public = account.as_public().unwrap();
+    let receive_public = account_public.extend(&path("m/0")).unwrap();
+    let key = DescriptorKey::from_string(Network::Testnet, account.as_string()).unwrap();
+    let paths: Vec<_> = (0..KEYS).map(|i| path(&format!("m/0/{}", i))).collect();
+    let full_paths: Vec<_> = (0..KEYS)
+        .map(|i| path(&format!("m/84h/1h/0h/0/{}", i)))
+        .collect();
+
+    let mut group = c.benchmark_group("bulk_derivation");
+    group.throughput(Throughput::Elements(KEYS as u64));
+    group.bench_function("secret_at_index", |b| {
+        b.iter(|| {
+            for index in 0..KEYS {
+                black_box(receive.at_index(index).unwrap());
+            }
+        })
+    });
+    group.bench_function("secret_as_public", |b| {
+        b.iter(|| {
+            for index in 0..KEYS {
+                black_box(receive.at_index(index).unwrap().as_public().unwrap());
+            }
+        })
+    });
+    group.bench_function("public_at_index", |b| {
+        b.iter(|| {
+            for index in 0..KEYS {
+                black_box(receive_public.at_index(index).unwrap());
+            }
+        })
+    });
+    group.bench_function("public_derive", |b| {
+        b.iter(|| {
+            for path in &paths {
+                black_box(account_public.derive(path).unwrap());
+            }
+        })
+    });
+    group.bench_function("descriptor_key_derive", |b| {
+        b.iter(|| {
+            for path in &paths {
+                black_box(key.derive(Some(path), None).unwrap());
+            }
+        })
+    });
+    group.bench_function("public_derive_batch", |b| {
+        b.iter(|| {
+            for key in receive_public.derive_batch(0..KEYS, 1).unwrap() {
+                black_box(key.unwrap());
+            }
+        })
+    });
+    group.bench_function("public_derive_batch_parallel", |b| {
+        b.iter(|| {
+            for key in receive_public.derive_batch(0..KEYS, 0).unwrap() {
+                black_box(key.unwrap());
+            }
+        })
+    });
+    group.bench_function("master_derive", |b| {
+        b.iter(|| {
+            for path in &full_paths {
+                black_box(master.derive(path).unwrap());
+            }
+        })
+    });
+    group.bench_function("master_derive_cached", |b| {
+        let cache = DerivationCache::new(2 * KEYS as usize);
+        b.iter(|| {
+            for path in &full_paths {
+                black_box(cache.derive(&master, path).unwrap());
+            }
+        })
+    });
+    group.finish();
+
+    // What every call used to pay before the context was shared.
+    c.bench_function("secp256k1_context_new", |b| {
+        b.iter(|| black_box(Secp256k1::new()))
+    });
+}
+
+criterion_group!(benches, bulk_derivation);
+criterion_main!(benches);
@@ -0,0 +1,215 @@
+//! The AEZ v5 enciphering scheme, limited to what aezeed needs: decrypting messages that are
+//! shorter than 32 bytes including the authenticator (AEZ-tiny).
+//!
+//! Follows the AEZ v5 specification by Hoang, Krovetz and Rogaway. Blocks are big-endian, so
+//! doubling shifts towards the first byte.
+
+use aes::hazmat::cipher_round;
+use blake2::digest::consts::U48;
+use blake2::{Blake2b, Digest};
+
+type Block = [u8; 16];
+
+const ZERO: Block = [0; 16];
+
+/// An AEZ key, expanded into its three AES subkeys.
+pub(crate) struct Aez {
+    i: Block,
+    j: Block,
+    l: Block,
+}
+
+impl Aez {
+    /// Expand `key`: a 48 byte key is used as is, any other is hashed with BLAKE2b-384.
+    pub(crate) fn new(key: &[u8]) -> Self {
+        let mut extracted = [0u8; 48];
+        if key.len() == 48 {
+            extracted.copy_from_slice(key);
+        } else {
+            extracted.copy_from_slice(&Blake2b::<U48>::digest(key));
+        }
+        let block = |index: usize| -> Block {
+            extracted[16 * index..16 * (index + 1)]
+                .try_into()
+                .expect("16 bytes")
+        };
+        Self {
+            i: block(0),
+            j: block(1),
+            l: block(2),
+        }
+    }
+
+    /// Decrypt `ciphertext` with an authenticator of `tau` bytes, returning the plaintext, or
+    /// `None` if the authenticator does not verify.
+    ///
+    /// Only messages whose ciphertext is shorter than 32 bytes are supported.
+    pub(crate) fn decrypt(
+        &self,
+        nonce: &[u8],
+        additional_data: &[&[u8]],
+        tau: usize,
+        ciphertext: &[u8],
+    ) -> Option<Vec<u8>> {
+        assert!(ciphertext.len() < 32, "only AEZ-tiny is implemented");
+        if ciphertext.len() < tau {
+            return None;
+        }
+        let delta = self.hash(nonce, additional_data, tau);
+        if ciphertext.len() == tau {
+            let tag = self.e10(3, &delta);
+            return (tag[..tau] == *ciphertext).then(Vec::new);
+        }
+        let plaintext = self.decipher_tiny(&delta, ciphertext);
+        let (message, authenticator) = plaintext.split_at(ciphertext.len() - tau);
+        authenticator
+            .iter()
+            .all(|byte| *byte == 0)
+            .then(|| message.to_vec())
+    }
+
+    /// AEZ-hash of the authenticator length, the nonce and the additional data.
+    fn hash(&self, nonce: &[u8], additional_data: &[&[u8]], tau: usize) -> Block {
+        let mut tau_block = ZERO;
+        tau_block[12..].copy_from_slice(&(8 * tau as u32).to_be_bytes());
+        let mut delta = self.e4(3, 1, &tau_block);
+        for (offset, component) in std::iter::once(&nonce).chain(additional_data).enumerate() {
+            let j = 4 + offset as u32;
+            let mut chunks = component.chunks_exact(16);
+            for (index, chunk) in chunks.by_ref().enumerate() {
+                let block = chunk.try_into().expect("16 bytes");
+                xor_into(&mut delta, &self.e4(j, index as u32 + 1, &block));
+            }
+            let remainder = chunks.remainder();
+            if component.is_empty() || !remainder.is_empty() {
+                let mut block = ZERO;
+                block[..remainder.len()].copy_from_slice(remainder);
+                block[remainder.len()] = 0x80;
+                xor_into(&mut delta, &self.e4(j, 0, &block));
+            }
+        }
+        delta
+    }
+
+    /// Invert the AEZ-tiny Feistel network for a ciphertext of 1 to 31 bytes.
+    fn decipher_tiny(&self, delta: &Block, ciphertext: &[u8]) -> Vec<u8> {
+        let bits = 8 * ciphertext.len();
+        let half = bits / 2;
+        let (rounds, j) = match ciphertext.len() {
+            16.. => (8, 6),
+            3.. => (10, 7),
+            2 => (16, 7),
+            _ => (24, 7),
+        };
+        let mut input = ciphertext.to_vec();
+        if ciphertext.len() < 16 {
+            self.flip_first_bit(delta, &mut input);
+        }
+
+        let mask = !0u128 << (128 - half);
+        let padding = 1u128 << (127 - half);
+        let delta = u128::from_be_bytes(*delta);
+        let round = |index: u32, half_block: u128| -> u128 {
+            let block = delta ^ half_block ^ padding ^ index as u128;
+            u128::from_be_bytes(self.e4(0, j, &block.to_be_bytes())) & mask
+        };
+        let mut left = read_bits(&input, 0, half);
+        let mut right = read_bits(&input, half, half);
+        for index in (0..rounds).step_by(2).rev() {
+            left ^= round(index + 1, right);
+            right ^= round(index, left);
+        }
+
+        let mut plaintext = vec![0u8; ciphertext.len()];
+        write_bits(&mut plaintext, 0, half, right);
+        write_bits(&mut plaintext, half, half, left);
+        plaintext
+    }
+
+    /// Flip the first bit of a short message depending on its other bits, the step AEZ-tiny
+    /// adds before the Feistel network when deciphering fewer than 16 bytes.
+    fn flip_first_bit(&self, delta: &Block, message: &mut [u8]) {
+        let mut block = ZERO;
+        block[..message.len()].copy_from_slice(message);
+        block[0] |= 0x80;
+        xor_into(&mut block, delta);
+        message[0] ^= self.e4(0, 3, &block)[0] & 0x80;
+    }
+
+    /// The tweakable block cipher E^{j,i} for j >= 0: four AES rounds keyed by J, I, L and
+    /// zero, after whitening with jJ + 2^ceil(i/8) I + (i mod 8) L.
+    fn e4(&self, j: u32, i: u32, block: &Block) -> Block {
+        let mut state = *block;
+        xor_into(&mut state, &multiply(&self.j, j));
+        xor_into(&mut state, &multiply(&self.i, 1 << i.div_ceil(8)));
+        xor_into(&mut state, &multiply(&self.l, i % 8));
+        for key in [&self.j, &self.i, &self.l, &ZERO] {
+            state = aes_round(&state, key);
+        }
+        state
+    }
+
+    /// The tweakable block cipher E^{-1,i}: ten AES rounds keyed by I, J, L in turn, after
+    /// whitening with iL.
+    fn e10(&self, i: u32, block: &Block) -> Block {
+        let mut state = *block;
+        xor_into(&mut state, &multiply(&self.l, i));
+        for key in [&self.i, &self.j, &self.l].into_iter().cycle().take(10) {
+            state = aes_round(&state, key);
+        }
+        state
+    }
+}
+
+/// One full AES encryption round: SubBytes, ShiftRows, MixColumns and AddRoundKey.
+fn aes_round(state: &Block, key: &Block) -> Block {
+    let mut block = (*state).into();
+    cipher_round(&mut block, &(*key).into());
+    block.into()
+}
+
+/// Multiply `block` by `factor` in GF(2^128).
+fn multiply(block: &Block, mut factor: u32) -> Block {
+    let mut result = ZERO;
+    let mut power = *block;
+    while factor > 0 {
+        if factor & 1 == 1 {
+            xor_into(&mut result, &power);
+        }
+        power = double(&power);
+        factor >>= 1;
+    }
+    result
+}
+
+fn double(block: &Block) -> Block {
+    let value = u128::from_be_bytes(*block);
+    let reduction = if value >> 127 == 1 { 0x87 } else { 0 };
+    ((value << 1) ^ reduction).to_be_bytes()
+}
+
+fn xor_into(target: &mut Block, other: &Block) {
+    target
+        .iter_mut()
+        .zip(other)
+        .for_each(|(byte, other)| *byte ^= other);
+}
+
+/// Read `len` bits of `bytes` from bit `start` on, aligned to the top of the result.
+fn read_bits(bytes: &[u8], start: usize, len: usize) -> u128 {
+    (0..len).fold(0, |acc, offset| {
+        let bit = start + offset;
+        let value = (bytes[bit / 8] >> (7 - bit % 8)) & 1;
+        acc | (value as u128) << (127 - offset)
+    })
+}
+
+/// Write the top `len` bits of `value` into `bytes` from bit `start` on.
+fn write_bits(bytes: &mut [u8], start: usize, len: usize, value: u128) {
+    for offset in 0..len {
+        let bit = start + offset;
+        if (value >> (127 - offset)) & 1 == 1 {
+            bytes[bit / 8] |= 0x80 >> (bit % 8);
+        }
+    }
+}
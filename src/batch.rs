@@ -0,0 +1,134 @@
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::thread;
+use std::vec;
+
+use bdk::bitcoin::util::bip32::{
+    ChildNumber, DerivationPath as BdkDerivationPath, ExtendedPubKey, Fingerprint,
+};
+use bdk::descriptor::{DescriptorXKey, Wildcard};
+use bdk::keys::DescriptorPublicKey as BdkDescriptorPublicKey;
+
+use crate::keys::extend_origin;
+use crate::secp::secp;
+use crate::{DescriptorPublicKey, Error};
+
+/// Child keys each thread derives per chunk. A chunk is the most a [`DerivedKeys`] holds at
+/// once, so memory stays bounded however long the range is.
+const KEYS_PER_THREAD: usize = 256;
+
+/// Bulk derivation: many child keys of one ranged key, in index order.
+impl DescriptorPublicKey {
+    /// Derive the child keys at each index in `range` on `threads` threads, as with
+    /// [`DescriptorPublicKey::at_index`].
+    ///
+    /// The pending derivation path is derived once up front and each key is then a single
+    /// child step, so this is much faster than calling `at_index` in a loop even on one
+    /// thread. Keys are derived lazily in chunks as the iterator is consumed and come out in
+    /// index order. `threads` of 0 uses the available parallelism; 1 derives on the calling
+    /// thread.
+    ///
+    /// Single keys and keys without a wildcard are rejected immediately. Indices of 2^31 and
+    /// above yield an error in place of their key.
+    ///
+    /// For output scripts, map each key through [`DescriptorPublicKey::script_pubkey`]; hashing
+    /// a key into its script costs far less than deriving it.
+    pub fn derive_batch(&self, range: Range<u32>, threads: usize) -> Result<DerivedKeys, Error> {
+        let descriptor_x_key = self.xpub()?;
+        match descriptor_x_key.wildcard {
+            Wildcard::None => return Err(Error::NonRangedKey),
+            Wildcard::Hardened => return Err(Error::HardenedDerivationFromPublic),
+            Wildcard::Unhardened => {}
+        }
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            threads => threads,
+        };
+        let (fingerprint, origin_path) = extend_origin(
+            &descriptor_x_key.origin,
+            || descriptor_x_key.xkey.fingerprint(),
+            &descriptor_x_key.derivation_path,
+        );
+        Ok(DerivedKeys {
+            parent: descriptor_x_key
+                .xkey
+                .derive_pub(secp(), &descriptor_x_key.derivation_path)?,
+            fingerprint,
+            origin_path,
+            range,
+            threads,
+            derived: Vec::new().into_iter(),
+        })
+    }
+}
+
+/// The child keys of [`DescriptorPublicKey::derive_batch`], in index order.
+#[derive(Debug)]
+pub struct DerivedKeys {
+    parent: ExtendedPubKey,
+    fingerprint: Fingerprint,
+    origin_path: BdkDerivationPath,
+    range: Range<u32>,
+    threads: usize,
+    derived: vec::IntoIter<Result<DescriptorPublicKey, Error>>,
+}
+
+impl DerivedKeys {
+    /// Derive the next chunk of the range, split between the threads.
+    fn derive_chunk(&mut self) -> Vec<Result<DescriptorPublicKey, Error>> {
+        let chunk_len = (self.threads * KEYS_PER_THREAD).min(self.range.len());
+        let chunk = self.range.start..self.range.start + chunk_len as u32;
+        self.range.start = chunk.end;
+
+        let per_thread = chunk_len.div_ceil(self.threads);
+        if per_thread >= chunk_len {
+            return chunk.map(|index| self.child(index)).collect();
+        }
+        let this = &*self;
+        thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .clone()
+                .step_by(per_thread)
+                .map(|start| {
+                    let part = start..chunk.end.min(start.saturating_add(per_thread as u32));
+                    scope.spawn(move || part.map(|index| this.child(index)).collect::<Vec<_>>())
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("deriving a child key does not panic"))
+                .collect()
+        })
+    }
+
+    /// The child key at `index`, with the same origin [`DescriptorPublicKey::at_index`] gives.
+    fn child(&self, index: u32) -> Result<DescriptorPublicKey, Error> {
+        let child_number = ChildNumber::from_normal_idx(index)?;
+        Ok(DescriptorPublicKey {
+            descriptor_public_key: BdkDescriptorPublicKey::XPub(DescriptorXKey {
+                origin: Some((self.fingerprint, self.origin_path.child(child_number))),
+                xkey: self.parent.ckd_pub(secp(), child_number)?,
+                derivation_path: BdkDerivationPath::default(),
+                wildcard: Wildcard::None,
+            }),
+        })
+    }
+}
+
+impl Iterator for DerivedKeys {
+    type Item = Result<DescriptorPublicKey, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.derived.len() == 0 && !self.range.is_empty() {
+            self.derived = self.derive_chunk().into_iter();
+        }
+        self.derived.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.derived.len() + self.range.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for DerivedKeys {}
@@ -0,0 +1,184 @@
+use bdk::bitcoin::base64;
+use bdk::bitcoin::hashes::hmac::{Hmac, HmacEngine};
+use bdk::bitcoin::hashes::{sha512, Hash, HashEngine};
+use bdk::bitcoin::secp256k1::SecretKey;
+use bdk::bitcoin::util::bip32::{
+    ChainCode, ChildNumber, DerivationPath as BdkDerivationPath, ExtendedPrivKey, Fingerprint,
+};
+use bdk::bitcoin::{Network, PrivateKey};
+use bdk::descriptor::{DescriptorXKey, Wildcard};
+use bdk::keys::bip39::{Language, Mnemonic as BdkMnemonic, WordCount};
+use bdk::keys::DescriptorSecretKey as BdkDescriptorSecretKey;
+
+use crate::secp::secp;
+use crate::{DescriptorSecretKey, Error, Mnemonic};
+
+const BIP85_PURPOSE: u32 = 83696968;
+const BIP39_APPLICATION: u32 = 39;
+const WIF_APPLICATION: u32 = 2;
+const XPRV_APPLICATION: u32 = 32;
+const HEX_APPLICATION: u32 = 128169;
+const BASE64_APPLICATION: u32 = 707764;
+const BASE85_APPLICATION: u32 = 707785;
+const BASE85_CHARSET: &[u8] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+/// BIP-85 deterministic entropy: child secrets derived from this key at hardened paths under
+/// `m/83696968'`, so one backed up master recreates any number of independent wallets and
+/// passwords.
+///
+/// The paths are derived from the extended key itself, which should be a master key such as
+/// one from [`DescriptorSecretKey::new`]. Every application takes an `index` below 2^31 to
+/// derive further children.
+impl DescriptorSecretKey {
+    /// Derive a BIP39 mnemonic of `word_count` words in `language`, at
+    /// `m/83696968'/39'/{language}'/{words}'/{index}'`.
+    pub fn bip85_mnemonic(
+        &self,
+        language: Language,
+        word_count: WordCount,
+        index: u32,
+    ) -> Result<Mnemonic, Error> {
+        let bytes = word_count as usize / 8;
+        let words = bytes as u32 * 3 / 4;
+        let entropy =
+            self.bip85_entropy(&[BIP39_APPLICATION, language_code(language), words, index])?;
+        let mnemonic = BdkMnemonic::from_entropy_in(language, &entropy[..bytes])?;
+        Ok(Mnemonic { mnemonic })
+    }
+
+    /// Derive a compressed private key in WIF, at `m/83696968'/2'/{index}'`.
+    pub fn bip85_wif(&self, index: u32) -> Result<String, Error> {
+        let entropy = self.bip85_entropy(&[WIF_APPLICATION, index])?;
+        let private_key = PrivateKey {
+            compressed: true,
+            network: self.key_network()?,
+            inner: secret_key(&entropy[..32])?,
+        };
+        Ok(private_key.to_wif())
+    }
+
+    /// Derive a master extended key, at `m/83696968'/32'/{index}'`.
+    ///
+    /// The first 32 bytes of entropy are the chain code and the last 32 the private key.
+    pub fn bip85_xprv(&self, index: u32) -> Result<DescriptorSecretKey, Error> {
+        let entropy = self.bip85_entropy(&[XPRV_APPLICATION, index])?;
+        let xprv = ExtendedPrivKey {
+            network: self.key_network()?,
+            depth: 0,
+            parent_fingerprint: Fingerprint::default(),
+            child_number: ChildNumber::from_normal_idx(0)?,
+            private_key: secret_key(&entropy[32..])?,
+            chain_code: ChainCode::from(&entropy[..32]),
+        };
+        Ok(DescriptorSecretKey {
+            descriptor_secret_key: BdkDescriptorSecretKey::XPrv(DescriptorXKey {
+                origin: None,
+                xkey: xprv,
+                derivation_path: BdkDerivationPath::master(),
+                wildcard: Wildcard::Unhardened,
+            }),
+        })
+    }
+
+    /// Derive `num_bytes` bytes of entropy, 16 to 64, as hex, at
+    /// `m/83696968'/128169'/{num_bytes}'/{index}'`.
+    pub fn bip85_hex(&self, num_bytes: u32, index: u32) -> Result<String, Error> {
+        check_length("hex entropy", num_bytes, 16, 64)?;
+        let entropy = self.bip85_entropy(&[HEX_APPLICATION, num_bytes, index])?;
+        Ok(entropy[..num_bytes as usize]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect())
+    }
+
+    /// Derive a base64 password of `length` characters, 20 to 86, at
+    /// `m/83696968'/707764'/{length}'/{index}'`.
+    pub fn bip85_password_base64(&self, length: u32, index: u32) -> Result<String, Error> {
+        check_length("a base64 password", length, 20, 86)?;
+        let entropy = self.bip85_entropy(&[BASE64_APPLICATION, length, index])?;
+        Ok(base64::encode(&entropy)[..length as usize].to_string())
+    }
+
+    /// Derive a base85 password of `length` characters, 10 to 80, at
+    /// `m/83696968'/707785'/{length}'/{index}'`.
+    ///
+    /// The alphabet is that of RFC 1924, as used by Python's `base64.b85encode`.
+    pub fn bip85_password_base85(&self, length: u32, index: u32) -> Result<String, Error> {
+        check_length("a base85 password", length, 10, 80)?;
+        let entropy = self.bip85_entropy(&[BASE85_APPLICATION, length, index])?;
+        let password: String = entropy
+            .chunks_exact(4)
+            .flat_map(|chunk| {
+                let mut value = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                let mut digits = [0u8; 5];
+                for digit in digits.iter_mut().rev() {
+                    *digit = BASE85_CHARSET[(value % 85) as usize];
+                    value /= 85;
+                }
+                digits
+            })
+            .map(char::from)
+            .collect();
+        Ok(password[..length as usize].to_string())
+    }
+
+    /// Return the 64 bytes of entropy at `m/83696968'` followed by the hardened `path`.
+    fn bip85_entropy(&self, path: &[u32]) -> Result<[u8; 64], Error> {
+        let descriptor_x_key = match &self.descriptor_secret_key {
+            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => descriptor_x_key,
+            BdkDescriptorSecretKey::SinglePriv(_) => return Err(Error::SingleKeyDerivation),
+        };
+        if !descriptor_x_key.derivation_path.is_master() {
+            return Err(Error::PendingDerivationPath);
+        }
+        let path = std::iter::once(BIP85_PURPOSE)
+            .chain(path.iter().copied())
+            .map(ChildNumber::from_hardened_idx)
+            .collect::<Result<Vec<_>, _>>()?;
+        let derived = descriptor_x_key
+            .xkey
+            .derive_priv(secp(), &BdkDerivationPath::from(path))?;
+
+        let mut engine = HmacEngine::<sha512::Hash>::new(b"bip-entropy-from-k");
+        engine.input(&derived.private_key.secret_bytes());
+        Ok(Hmac::from_engine(engine).into_inner())
+    }
+
+    fn key_network(&self) -> Result<Network, Error> {
+        match &self.descriptor_secret_key {
+            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => Ok(descriptor_x_key.xkey.network),
+            BdkDescriptorSecretKey::SinglePriv(single) => Ok(single.key.network),
+        }
+    }
+}
+
+/// Return the BIP-85 code of a BIP39 wordlist.
+fn language_code(language: Language) -> u32 {
+    match language {
+        Language::English => 0,
+        Language::Japanese => 1,
+        Language::Korean => 2,
+        Language::Spanish => 3,
+        Language::SimplifiedChinese => 4,
+        Language::TraditionalChinese => 5,
+        Language::French => 6,
+        Language::Italian => 7,
+        Language::Czech => 8,
+    }
+}
+
+fn check_length(what: &str, length: u32, min: u32, max: u32) -> Result<(), Error> {
+    if (min..=max).contains(&length) {
+        Ok(())
+    } else {
+        Err(Error::InvalidBip85(format!(
+            "{} must be {} to {} long, not {}",
+            what, min, max, length
+        )))
+    }
+}
+
+fn secret_key(bytes: &[u8]) -> Result<SecretKey, Error> {
+    SecretKey::from_slice(bytes).map_err(|e| Error::InvalidKey(e.to_string()))
+}
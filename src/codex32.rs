@@ -0,0 +1,407 @@
+use bdk::bitcoin::secp256k1::rand::{thread_rng, RngCore};
+
+use crate::Error;
+
+const CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const HRP: &str = "ms";
+const SECRET_INDEX: char = 's';
+/// Share indices in the order shares are handed out, every character but `s`.
+const SHARE_INDICES: &str = "acdefghjklmnpqrtuvwxyz023456789";
+const HEADER_CHARS: usize = 6;
+const MIN_SEED_BYTES: usize = 16;
+const MAX_SEED_BYTES: usize = 64;
+
+/// The checksum generator and target residue, as characters.
+struct Checksum {
+    generator: &'static str,
+    target: &'static str,
+}
+
+const SHORT_CHECKSUM: Checksum = Checksum {
+    generator: "em3gqeeelmcss",
+    target: "secretshare32",
+};
+const LONG_CHECKSUM: Checksum = Checksum {
+    generator: "02e6fe4xh4x9kyh",
+    target: "secretshare32ex",
+};
+
+/// A codex32 (BIP-93) string: a master seed, or one share of it, that can be checked and
+/// recombined by hand.
+///
+/// A string such as `ms12namea320zyxwvu...` holds the threshold (`0` for an unshared seed, or
+/// `2` to `9`), a four character identifier, the share index and the seed data, followed by
+/// a BCH checksum. The master seed itself has share index `s`. Strings are kept lowercase.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Codex32 {
+    string: String,
+}
+
+impl Codex32 {
+    /// Parse and verify a codex32 string, in either all lowercase or all uppercase.
+    pub fn from_string(string: String) -> Result<Self, Error> {
+        if string.chars().any(|c| c.is_ascii_lowercase())
+            && string.chars().any(|c| c.is_ascii_uppercase())
+        {
+            return Err(invalid("mixed case".to_string()));
+        }
+        let string = string.to_ascii_lowercase();
+        let data = match string.rsplit_once('1') {
+            Some((HRP, data)) => data,
+            _ => return Err(invalid("the string must start with \"ms1\"".to_string())),
+        };
+        let checksum = match string.len() {
+            48..=93 => SHORT_CHECKSUM,
+            125..=127 => LONG_CHECKSUM,
+            len => return Err(invalid(format!("{} characters is not a valid length", len))),
+        };
+        let values = data
+            .chars()
+            .map(|c| value(c).ok_or_else(|| invalid(format!("{:?} is not a bech32 character", c))))
+            .collect::<Result<Vec<_>, _>>()?;
+        if residue(&checksum, &values) != values_of(checksum.target) {
+            return Err(invalid("invalid checksum".to_string()));
+        }
+
+        let codex32 = Self { string };
+        match codex32.threshold_char() {
+            '0' if codex32.share_index() != SECRET_INDEX => {
+                return Err(invalid(
+                    "an unshared seed must have share index \"s\"".to_string(),
+                ))
+            }
+            '0' | '2'..='9' => {}
+            c => return Err(invalid(format!("{:?} is not a valid threshold", c))),
+        }
+        let padding = codex32.payload().len() * 5 % 8;
+        if padding > 4 {
+            return Err(invalid(format!(
+                "{} bits of padding is more than 4",
+                padding
+            )));
+        }
+        Ok(codex32)
+    }
+
+    /// Encode `seed`, of 16 to 64 bytes, as an unshared codex32 string with threshold `0`.
+    ///
+    /// The `identifier` is any four bech32 characters.
+    pub fn from_seed(identifier: String, seed: Vec<u8>) -> Result<Self, Error> {
+        Self::encode('0', &identifier, SECRET_INDEX, &seed)
+    }
+
+    /// Split `seed` into `share_count` shares, any `threshold` of which recover it.
+    ///
+    /// As BIP-93 describes, the first `threshold - 1` shares (`a`, `c`, `d`, ...) are random
+    /// and the others are interpolated from them and the seed. `threshold` is 2 to 9 and
+    /// `share_count` at most 31.
+    pub fn split(
+        seed: Vec<u8>,
+        identifier: String,
+        threshold: u8,
+        share_count: u8,
+    ) -> Result<Vec<Codex32>, Error> {
+        if !(2..=9).contains(&threshold) {
+            return Err(Error::InvalidShares(format!(
+                "the codex32 threshold must be 2 to 9, not {}",
+                threshold
+            )));
+        }
+        if share_count < threshold || usize::from(share_count) > SHARE_INDICES.len() {
+            return Err(Error::InvalidShares(format!(
+                "{} shares cannot have a threshold of {}",
+                share_count, threshold
+            )));
+        }
+        let threshold_char = char::from(b'0' + threshold);
+        let mut points = vec![Self::encode(
+            threshold_char,
+            &identifier,
+            SECRET_INDEX,
+            &seed,
+        )?];
+        let mut shares = Vec::with_capacity(usize::from(share_count));
+        for index in SHARE_INDICES.chars().take(usize::from(threshold) - 1) {
+            let mut random = vec![0u8; seed.len()];
+            thread_rng().fill_bytes(&mut random);
+            let share = Self::encode(threshold_char, &identifier, index, &random)?;
+            points.push(share.clone());
+            shares.push(share);
+        }
+        for index in SHARE_INDICES
+            .chars()
+            .take(usize::from(share_count))
+            .skip(usize::from(threshold) - 1)
+        {
+            shares.push(Self::interpolate(points.clone(), index)?);
+        }
+        Ok(shares)
+    }
+
+    /// Recover the share with `share_index` from at least threshold many `shares` of the same
+    /// seed. Share index `s` recovers the seed itself, see [`Codex32::combine`].
+    pub fn interpolate(shares: Vec<Codex32>, share_index: char) -> Result<Self, Error> {
+        let first = shares
+            .first()
+            .ok_or_else(|| Error::InvalidShares("no shares were given".to_string()))?;
+        let target = value(share_index.to_ascii_lowercase()).ok_or_else(|| {
+            Error::InvalidShares(format!("{:?} is not a valid share index", share_index))
+        })?;
+
+        let mut points: Vec<&Codex32> = Vec::new();
+        for share in &shares {
+            if share.threshold_char() != first.threshold_char()
+                || share.identifier() != first.identifier()
+                || share.string.len() != first.string.len()
+            {
+                return Err(Error::InvalidShares(
+                    "all shares must have the same threshold, identifier and length".to_string(),
+                ));
+            }
+            match points
+                .iter()
+                .find(|point| point.share_index() == share.share_index())
+            {
+                Some(point) if point.string == share.string => {}
+                Some(_) => {
+                    return Err(Error::InvalidShares(format!(
+                        "two different shares have index {:?}",
+                        share.share_index()
+                    )))
+                }
+                None => points.push(share),
+            }
+        }
+        if let Some(point) = points
+            .iter()
+            .find(|point| point.share_index() == share_index.to_ascii_lowercase())
+        {
+            return Ok((*point).clone());
+        }
+        let threshold = usize::from(first.threshold()).max(1);
+        if points.len() < threshold {
+            return Err(Error::InvalidShares(format!(
+                "{} shares are needed but {} were given",
+                threshold,
+                points.len()
+            )));
+        }
+
+        let points = &points[..threshold];
+        let xs: Vec<u8> = points.iter().map(|point| point.values()[5]).collect();
+        let mut result = vec![0u8; first.values().len()];
+        for (i, point) in points.iter().enumerate() {
+            let (numerator, denominator) = xs.iter().enumerate().filter(|&(j, _)| j != i).fold(
+                (1, 1),
+                |(numerator, denominator), (_, &x)| {
+                    (
+                        gf32_mul(numerator, target ^ x),
+                        gf32_mul(denominator, xs[i] ^ x),
+                    )
+                },
+            );
+            let basis = gf32_mul(numerator, gf32_inverse(denominator));
+            for (result, value) in result.iter_mut().zip(point.values()) {
+                *result ^= gf32_mul(basis, value);
+            }
+        }
+        let data: String = result.into_iter().map(character).collect();
+        Ok(Self {
+            string: format!("{}1{}", HRP, data),
+        })
+    }
+
+    /// Recover the seed string, with share index `s`, from at least threshold many `shares`.
+    pub fn combine(shares: Vec<Codex32>) -> Result<Self, Error> {
+        Self::interpolate(shares, SECRET_INDEX)
+    }
+
+    /// Return the threshold, `0` for an unshared seed.
+    pub fn threshold(&self) -> u8 {
+        self.threshold_char() as u8 - b'0'
+    }
+
+    /// Return the four character identifier shared by all shares of a seed.
+    pub fn identifier(&self) -> String {
+        self.data()[1..5].to_string()
+    }
+
+    /// Return the share index, `s` for the seed itself.
+    pub fn share_index(&self) -> char {
+        self.data()[5..]
+            .chars()
+            .next()
+            .expect("parsed strings have a header")
+    }
+
+    /// Return the master seed held by a string with share index `s`.
+    pub fn master_seed(&self) -> Result<Vec<u8>, Error> {
+        if self.share_index() != SECRET_INDEX {
+            return Err(Error::InvalidShares(format!(
+                "share {:?} is not the seed, combine it with other shares first",
+                self.share_index()
+            )));
+        }
+        let bits: Vec<bool> = self
+            .payload()
+            .chars()
+            .flat_map(|c| {
+                let value = value(c).expect("parsed strings are bech32");
+                (0..5).rev().map(move |bit| (value >> bit) & 1 == 1)
+            })
+            .collect();
+        Ok(bits
+            .chunks_exact(8)
+            .map(|byte| {
+                byte.iter()
+                    .fold(0u8, |acc, &bit| (acc << 1) | u8::from(bit))
+            })
+            .collect())
+    }
+
+    /// Return the lowercase codex32 string.
+    pub fn as_string(&self) -> String {
+        self.string.clone()
+    }
+
+    fn encode(
+        threshold: char,
+        identifier: &str,
+        share_index: char,
+        seed: &[u8],
+    ) -> Result<Self, Error> {
+        let identifier = identifier.to_ascii_lowercase();
+        if identifier.chars().count() != 4 || identifier.chars().any(|c| value(c).is_none()) {
+            return Err(invalid(format!(
+                "{:?} is not four bech32 characters",
+                identifier
+            )));
+        }
+        if !(MIN_SEED_BYTES..=MAX_SEED_BYTES).contains(&seed.len()) {
+            return Err(invalid(format!(
+                "a seed of {} bytes cannot be encoded, it must be 16 to 64",
+                seed.len()
+            )));
+        }
+
+        let bits: Vec<bool> = seed
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |bit| (byte >> bit) & 1 == 1))
+            .collect();
+        let payload: String = bits
+            .chunks(5)
+            .map(|chunk| {
+                let value = chunk
+                    .iter()
+                    .fold(0u8, |acc, &bit| (acc << 1) | u8::from(bit));
+                character(value << (5 - chunk.len()))
+            })
+            .collect();
+        let data = format!("{}{}{}{}", threshold, identifier, share_index, payload);
+        let checksum = match HRP.len() + 1 + data.len() + SHORT_CHECKSUM.target.len() {
+            48..=93 => SHORT_CHECKSUM,
+            _ if HRP.len() + 1 + data.len() + LONG_CHECKSUM.target.len() >= 125 => LONG_CHECKSUM,
+            _ => {
+                return Err(invalid(format!(
+                    "a seed of {} bytes has no codex32 encoding",
+                    seed.len()
+                )))
+            }
+        };
+        let mut values = values_of(&data);
+        values.extend(values_of(checksum.target));
+        let checksum: String = residue(&checksum, &values)
+            .into_iter()
+            .map(character)
+            .collect();
+        Self::from_string(format!("{}1{}{}", HRP, data, checksum))
+    }
+
+    /// Return the part after `ms1`.
+    fn data(&self) -> &str {
+        &self.string[HRP.len() + 1..]
+    }
+
+    fn threshold_char(&self) -> char {
+        self.data()
+            .chars()
+            .next()
+            .expect("parsed strings have a header")
+    }
+
+    fn payload(&self) -> &str {
+        let checksum = if self.string.len() > 93 {
+            LONG_CHECKSUM.target.len()
+        } else {
+            SHORT_CHECKSUM.target.len()
+        };
+        let data = self.data();
+        &data[HEADER_CHARS..data.len() - checksum]
+    }
+
+    fn values(&self) -> Vec<u8> {
+        values_of(self.data())
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::InvalidCodex32(message)
+}
+
+fn value(c: char) -> Option<u8> {
+    CHARSET.find(c).map(|index| index as u8)
+}
+
+fn character(value: u8) -> char {
+    char::from(CHARSET.as_bytes()[usize::from(value)])
+}
+
+fn values_of(string: &str) -> Vec<u8> {
+    string
+        .chars()
+        .map(|c| value(c).expect("only bech32 characters are passed"))
+        .collect()
+}
+
+/// Multiply in GF(32), modulo x^5 + x^3 + 1 as in bech32.
+fn gf32_mul(a: u8, b: u8) -> u8 {
+    (0..5).fold(0, |product, bit| {
+        if (b >> bit) & 1 == 0 {
+            return product;
+        }
+        let shifted = (0..bit).fold(a, |value, _| {
+            let value = value << 1;
+            if value & 32 != 0 {
+                value ^ 0b101001
+            } else {
+                value
+            }
+        });
+        product ^ shifted
+    })
+}
+
+fn gf32_inverse(a: u8) -> u8 {
+    // a^31 = 1 for every non-zero element, so a^30 is its inverse.
+    (0..29).fold(a, |power, _| gf32_mul(power, a))
+}
+
+/// Return the residue of the `ms` prefix followed by `values`, modulo the checksum generator.
+fn residue(checksum: &Checksum, values: &[u8]) -> Vec<u8> {
+    let generator = values_of(checksum.generator);
+    let mut residue = vec![0u8; generator.len()];
+    *residue.last_mut().expect("checksums are not empty") = 1;
+    let prefix = HRP
+        .bytes()
+        .map(|byte| byte >> 5)
+        .chain([0])
+        .chain(HRP.bytes().map(|byte| byte & 31));
+    for value in prefix.chain(values.iter().copied()) {
+        let top = residue.remove(0);
+        residue.push(value);
+        for (residue, generator) in residue.iter_mut().zip(&generator) {
+            *residue ^= gf32_mul(*generator, top);
+        }
+    }
+    residue
+}
@@ -0,0 +1,237 @@
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use bdk::bitcoin::secp256k1::SecretKey;
+use bdk::bitcoin::util::bip32::{
+    ChainCode, ChildNumber, DerivationPath as BdkDerivationPath, ExtendedPrivKey, Fingerprint,
+};
+use bdk::bitcoin::{Network, XpubIdentifier};
+use bdk::descriptor::DescriptorXKey;
+use bdk::keys::DescriptorSecretKey as BdkDescriptorSecretKey;
+use lru::LruCache;
+use zeroize::Zeroize;
+
+use crate::keys::extend_origin;
+use crate::secp::secp;
+use crate::{DerivationPath, DescriptorSecretKey, Error, PendingPathPolicy};
+
+/// A bounded cache of intermediate extended private keys, so that deriving many paths with a
+/// common prefix, such as `m/84h/1h/0h/0/5` and `m/84h/1h/0h/0/6`, only derives the shared
+/// steps once.
+///
+/// Keys are cached by the fingerprint of the key derived from and the path derived so far.
+/// The least recently used entries are evicted once `capacity` is reached, and the private key
+/// and chain code of every entry are wiped from memory when it is evicted, cleared or dropped.
+/// The cache can be shared between threads.
+pub struct DerivationCache {
+    entries: Mutex<Entries>,
+}
+
+/// Hit and miss counts of a [`DerivationCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DerivationCacheStats {
+    /// Derivations that started from a cached intermediate key.
+    pub hits: u64,
+    /// Derivations that found no cached prefix and started from the key itself.
+    pub misses: u64,
+    /// Entries removed to stay within the capacity.
+    pub evictions: u64,
+    /// Entries currently cached.
+    pub len: usize,
+}
+
+struct Entries {
+    cache: Option<LruCache<(Fingerprint, BdkDerivationPath), CachedXprv>>,
+    stats: DerivationCacheStats,
+}
+
+impl DerivationCache {
+    /// Create a cache holding at most `capacity` intermediate keys. A capacity of 0 caches
+    /// nothing.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(Entries {
+                cache: NonZeroUsize::new(capacity).map(LruCache::new),
+                stats: DerivationCacheStats::default(),
+            }),
+        }
+    }
+
+    /// Derive a child of `key` along `path` as [`DescriptorSecretKey::derive`] does, reusing
+    /// and caching the intermediate keys on the way.
+    pub fn derive(
+        &self,
+        key: &DescriptorSecretKey,
+        path: &DerivationPath,
+    ) -> Result<DescriptorSecretKey, Error> {
+        self.derive_with_policy(key, path, PendingPathPolicy::default())
+    }
+
+    /// Derive a child of `key` along `path` as [`DescriptorSecretKey::derive_with_policy`]
+    /// does, reusing and caching the intermediate keys on the way.
+    pub fn derive_with_policy(
+        &self,
+        key: &DescriptorSecretKey,
+        path: &DerivationPath,
+        policy: PendingPathPolicy,
+    ) -> Result<DescriptorSecretKey, Error> {
+        let descriptor_x_key = key.xprv()?;
+        let path = policy.apply(
+            &descriptor_x_key.derivation_path,
+            path.derivation_path.clone(),
+        )?;
+        let root = &descriptor_x_key.xkey;
+        let identifier = root.identifier(secp());
+        let fingerprint = Fingerprint::from(&identifier[0..4]);
+        let steps = path.as_ref();
+
+        let (mut xprv, cached_steps) = self.longest_prefix(root, identifier, fingerprint, steps);
+        for (index, child) in steps.iter().enumerate().skip(cached_steps) {
+            xprv = xprv.ckd_priv(secp(), *child)?;
+            self.insert(
+                fingerprint,
+                &steps[..=index],
+                CachedXprv::new(&xprv, identifier),
+            );
+        }
+
+        let key_source = extend_origin(&descriptor_x_key.origin, || fingerprint, &path);
+        Ok(DescriptorSecretKey {
+            descriptor_secret_key: BdkDescriptorSecretKey::XPrv(DescriptorXKey {
+                origin: Some(key_source),
+                xkey: xprv,
+                derivation_path: BdkDerivationPath::default(),
+                wildcard: descriptor_x_key.wildcard,
+            }),
+        })
+    }
+
+    /// Return the hit, miss and eviction counts so far and the number of cached keys.
+    pub fn stats(&self) -> DerivationCacheStats {
+        let entries = self.lock();
+        DerivationCacheStats {
+            len: entries.cache.as_ref().map_or(0, LruCache::len),
+            ..entries.stats
+        }
+    }
+
+    /// Remove and wipe every cached key. The statistics are kept.
+    pub fn clear(&self) {
+        if let Some(cache) = &mut self.lock().cache {
+            cache.clear();
+        }
+    }
+
+    /// Find the longest prefix of `steps` that is cached for `root`, returning its key and
+    /// length, or `root` itself and 0.
+    fn longest_prefix(
+        &self,
+        root: &ExtendedPrivKey,
+        identifier: XpubIdentifier,
+        fingerprint: Fingerprint,
+        steps: &[ChildNumber],
+    ) -> (ExtendedPrivKey, usize) {
+        if steps.is_empty() {
+            return (*root, 0);
+        }
+        let mut entries = self.lock();
+        let Entries { cache, stats } = &mut *entries;
+        let found = cache.as_mut().and_then(|cache| {
+            (1..=steps.len()).rev().find_map(|len| {
+                let prefix = BdkDerivationPath::from(&steps[..len]);
+                cache
+                    .get(&(fingerprint, prefix))
+                    .and_then(|cached| cached.to_xprv(root.network, identifier))
+                    .map(|xprv| (xprv, len))
+            })
+        });
+        match found {
+            Some(found) => {
+                stats.hits += 1;
+                found
+            }
+            None => {
+                stats.misses += 1;
+                (*root, 0)
+            }
+        }
+    }
+
+    fn insert(&self, fingerprint: Fingerprint, steps: &[ChildNumber], cached: CachedXprv) {
+        let mut entries = self.lock();
+        let Entries { cache, stats } = &mut *entries;
+        if let Some(cache) = cache {
+            let key = (fingerprint, BdkDerivationPath::from(steps));
+            // The replaced or evicted entry is wiped as it drops.
+            if let Some((evicted, _)) = cache.push(key.clone(), cached) {
+                if evicted != key {
+                    stats.evictions += 1;
+                }
+            }
+        }
+    }
+
+    // A panic while the lock was held cannot leave a half written entry, so a poisoned cache
+    // is still consistent.
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Never print the cached keys.
+impl fmt::Debug for DerivationCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DerivationCache")
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+/// An intermediate extended private key, with its secret parts in arrays that are wiped on
+/// drop.
+struct CachedXprv {
+    root: XpubIdentifier,
+    depth: u8,
+    parent_fingerprint: Fingerprint,
+    child_number: ChildNumber,
+    private_key: [u8; 32],
+    chain_code: [u8; 32],
+}
+
+impl CachedXprv {
+    fn new(xprv: &ExtendedPrivKey, root: XpubIdentifier) -> Self {
+        Self {
+            root,
+            depth: xprv.depth,
+            parent_fingerprint: xprv.parent_fingerprint,
+            child_number: xprv.child_number,
+            private_key: xprv.private_key.secret_bytes(),
+            chain_code: xprv.chain_code.to_bytes(),
+        }
+    }
+
+    /// The cached key on `network`, or `None` if it was derived from a different root whose
+    /// fingerprint collides with the one asked for.
+    fn to_xprv(&self, network: Network, root: XpubIdentifier) -> Option<ExtendedPrivKey> {
+        if self.root != root {
+            return None;
+        }
+        Some(ExtendedPrivKey {
+            network,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+            private_key: SecretKey::from_slice(&self.private_key)
+                .expect("a cached private key is valid"),
+            chain_code: ChainCode::from(&self.chain_code[..]),
+        })
+    }
+}
+
+impl Drop for CachedXprv {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+        self.chain_code.zeroize();
+    }
+}
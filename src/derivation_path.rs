@@ -0,0 +1,20 @@
+use std::str::FromStr;
+
+use bdk::bitcoin::util::bip32::DerivationPath as BdkDerivationPath;
+
+use crate::Error;
+
+/// A BIP32 derivation path such as `m/84h/1h/0h`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DerivationPath {
+    pub(crate) derivation_path: BdkDerivationPath,
+}
+
+impl DerivationPath {
+    /// Parse a derivation path, with or without the leading `m`.
+    pub fn new(path: String) -> Result<Self, Error> {
+        BdkDerivationPath::from_str(&path)
+            .map(|derivation_path| DerivationPath { derivation_path })
+            .map_err(Error::InvalidDerivationPath)
+    }
+}
@@ -0,0 +1,416 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use bdk::bitcoin::Network;
+use bdk::descriptor::{DescriptorXKey, Legacy, Wildcard};
+use bdk::keys::{
+    DescriptorKey as BdkDescriptorKey, DescriptorPublicKey, DescriptorSecretKey, IntoDescriptorKey,
+};
+use bdk::keys::{ExtScriptContext, ScriptContextEnum};
+use bdk::miniscript::descriptor::SinglePubKey;
+use bdk::miniscript::ScriptContext;
+
+use crate::keys::{
+    clone_secret_key, parse_public_key, parse_secret_key, public_key_master_fingerprint,
+    secret_key_as_public, secret_key_master_fingerprint,
+};
+use crate::secp::secp;
+use crate::{DerivationPath, Error, Mnemonic, PendingPathPolicy};
+
+/// A descriptor key that is either secret or public, together with the networks it is valid on.
+///
+/// `Ctx` is the script context the key is used in: [`Legacy`] by default, [`Segwitv0`] or
+/// [`Tap`]. Keys are created for legacy scripts and moved to another context with
+/// [`DescriptorKey::to_context`], which checks that the key is allowed there.
+///
+/// Like the other key wrappers it is an immutable value that can be cloned, compared, hashed
+/// and shared between threads.
+///
+/// It is one type for keys whose kind is only known at runtime, such as parsed user input.
+/// Deriving delegates to [`DescriptorSecretKey`] or [`DescriptorPublicKey`], so the results
+/// match theirs; [`DescriptorKey::derive`] takes both the path to derive and the path to
+/// extend with, where those wrappers have separate `derive` and `extend` methods. Use them
+/// when the kind of key is known, and convert with `DescriptorKey::try_from`.
+///
+/// [`Segwitv0`]: crate::Segwitv0
+/// [`Tap`]: crate::Tap
+/// [`DescriptorSecretKey`]: crate::DescriptorSecretKey
+/// [`DescriptorPublicKey`]: crate::DescriptorPublicKey
+pub struct DescriptorKey<Ctx: ScriptContext = Legacy> {
+    pub(crate) descriptor_key: BdkDescriptorKey<Ctx>,
+}
+
+impl DescriptorKey {
+    /// Create a master secret key from a BIP39 mnemonic and an optional passphrase.
+    ///
+    /// The key has an unhardened wildcard; use [`DescriptorKey::with_wildcard`] to change it.
+    pub fn new(
+        network: Network,
+        mnemonic: &Mnemonic,
+        password: Option<String>,
+    ) -> Result<Self, Error> {
+        Self::try_from(&crate::DescriptorSecretKey::new(
+            network, mnemonic, password,
+        )?)
+    }
+
+    /// Create a master secret key from raw BIP39 entropy of 16 to 32 bytes and an optional
+    /// passphrase, as [`DescriptorKey::new`] does from the mnemonic encoding it.
+    pub fn from_entropy(
+        network: Network,
+        entropy: Vec<u8>,
+        password: Option<String>,
+    ) -> Result<Self, Error> {
+        Self::try_from(&crate::DescriptorSecretKey::from_entropy(
+            network, entropy, password,
+        )?)
+    }
+
+    /// Create a master secret key from a BIP32 seed of 16 to 64 bytes.
+    ///
+    /// The key has an unhardened wildcard; use [`DescriptorKey::with_wildcard`] to change it.
+    pub fn from_seed(network: Network, seed: Vec<u8>) -> Result<Self, Error> {
+        Self::try_from(&crate::DescriptorSecretKey::from_seed(network, seed)?)
+    }
+
+    /// Create a master key from SLIP-39 `shares` and the passphrase they were made with, using
+    /// the recovered master secret as the BIP32 seed.
+    pub fn from_slip39(
+        network: Network,
+        shares: Vec<String>,
+        passphrase: Option<String>,
+    ) -> Result<Self, Error> {
+        Self::try_from(&crate::DescriptorSecretKey::from_slip39(
+            network, shares, passphrase,
+        )?)
+    }
+
+    /// Create a master key from codex32 `shares`, or a single unshared codex32 string, using
+    /// the recovered master seed as the BIP32 seed.
+    pub fn from_codex32(network: Network, shares: Vec<String>) -> Result<Self, Error> {
+        Self::try_from(&crate::DescriptorSecretKey::from_codex32(network, shares)?)
+    }
+
+    /// Parse a secret or public key in descriptor notation, validated as in
+    /// [`DescriptorSecretKey::from_string`](crate::DescriptorSecretKey::from_string) and
+    /// [`DescriptorPublicKey::from_string`](crate::DescriptorPublicKey::from_string).
+    ///
+    /// X-only public keys are only valid in taproot scripts; parse them with
+    /// [`DescriptorKey::from_string_in_context`].
+    pub fn from_string(network: Network, key: String) -> Result<Self, Error> {
+        Self::from_string_in_context(network, key)
+    }
+}
+
+impl<Ctx: ScriptContext + 'static> DescriptorKey<Ctx> {
+    /// Parse a key as [`DescriptorKey::from_string`] does, for use in the `Ctx` script
+    /// context, e.g. `DescriptorKey::<Tap>::from_string_in_context(network, key)`.
+    ///
+    /// Returns [`Error::InvalidScriptContext`] for single keys that are not allowed in `Ctx`.
+    pub fn from_string_in_context(network: Network, key: String) -> Result<Self, Error> {
+        let descriptor_key = match parse_secret_key(network, &key) {
+            Ok(descriptor_secret_key) => descriptor_secret_key.into_descriptor_key()?,
+            // Only strings that are no secret key at all are parsed as public keys, so a secret
+            // key on the wrong network or with a bad origin keeps its error.
+            Err(Error::InvalidKey(_)) => parse_public_key(network, &key)?.into_descriptor_key()?,
+            Err(e) => return Err(e),
+        };
+        check_context(&descriptor_key)?;
+        Ok(Self { descriptor_key })
+    }
+
+    /// Derive the key along `origin_path`, then append `descriptor_path` to its derivation path.
+    ///
+    /// This is [`DescriptorSecretKey::derive`] followed by [`DescriptorSecretKey::extend`], or
+    /// the same on [`DescriptorPublicKey`] for public keys, so the wrappers agree on every
+    /// ordering: extending with `m/0` twice gives `.../0/0/*` here as well. A derivation path
+    /// the key already has is derived before `origin_path`, as with [`PendingPathPolicy::Fold`].
+    ///
+    /// Single keys cannot be derived.
+    ///
+    /// [`DescriptorSecretKey::derive`]: crate::DescriptorSecretKey::derive
+    /// [`DescriptorSecretKey::extend`]: crate::DescriptorSecretKey::extend
+    /// [`DescriptorPublicKey`]: crate::DescriptorPublicKey
+    pub fn derive(
+        &self,
+        origin_path: Option<&DerivationPath>,
+        descriptor_path: Option<&DerivationPath>,
+    ) -> Result<Self, Error> {
+        self.derive_with_policy(origin_path, descriptor_path, PendingPathPolicy::default())
+    }
+
+    /// Like [`DescriptorKey::derive`], handling the key's current derivation path according to
+    /// `policy` when an `origin_path` is given.
+    pub fn derive_with_policy(
+        &self,
+        origin_path: Option<&DerivationPath>,
+        descriptor_path: Option<&DerivationPath>,
+        policy: PendingPathPolicy,
+    ) -> Result<Self, Error> {
+        let descriptor_key = match &self.descriptor_key {
+            BdkDescriptorKey::Public(descriptor_public_key, networks, _) => {
+                let mut key = crate::DescriptorPublicKey {
+                    descriptor_public_key: descriptor_public_key.clone(),
+                };
+                key.xpub()?;
+                if let Some(path) = origin_path {
+                    key = key.derive_with_policy(path, policy)?;
+                }
+                if let Some(path) = descriptor_path {
+                    key = key.extend(path)?;
+                }
+                BdkDescriptorKey::from_public(key.descriptor_public_key, networks.clone())
+            }
+            BdkDescriptorKey::Secret(descriptor_secret_key, networks, _) => {
+                let mut key = crate::DescriptorSecretKey {
+                    descriptor_secret_key: clone_secret_key(descriptor_secret_key),
+                };
+                key.xprv()?;
+                if let Some(path) = origin_path {
+                    key = key.derive_with_policy(path, policy)?;
+                }
+                if let Some(path) = descriptor_path {
+                    key = key.extend(path)?;
+                }
+                BdkDescriptorKey::from_secret(key.descriptor_secret_key, networks.clone())
+            }
+        };
+        Ok(Self { descriptor_key })
+    }
+
+    /// Return the wildcard of this key; single keys have [`Wildcard::None`].
+    pub fn wildcard(&self) -> Wildcard {
+        match &self.descriptor_key {
+            BdkDescriptorKey::Public(DescriptorPublicKey::XPub(xpub), _, _) => xpub.wildcard,
+            BdkDescriptorKey::Secret(DescriptorSecretKey::XPrv(xprv), _, _) => xprv.wildcard,
+            _ => Wildcard::None,
+        }
+    }
+
+    /// Return a copy of this key with `wildcard` as its wildcard.
+    ///
+    /// Public keys cannot have a hardened wildcard, and single keys only accept
+    /// [`Wildcard::None`].
+    pub fn with_wildcard(&self, wildcard: Wildcard) -> Result<Self, Error> {
+        let descriptor_key = match &self.descriptor_key {
+            BdkDescriptorKey::Public(DescriptorPublicKey::XPub(_), _, _)
+                if wildcard == Wildcard::Hardened =>
+            {
+                return Err(Error::HardenedDerivationFromPublic)
+            }
+            BdkDescriptorKey::Public(DescriptorPublicKey::XPub(xpub), networks, _) => {
+                BdkDescriptorKey::from_public(
+                    DescriptorPublicKey::XPub(DescriptorXKey {
+                        wildcard,
+                        ..xpub.clone()
+                    }),
+                    networks.clone(),
+                )
+            }
+            BdkDescriptorKey::Secret(DescriptorSecretKey::XPrv(xprv), networks, _) => {
+                BdkDescriptorKey::from_secret(
+                    DescriptorSecretKey::XPrv(DescriptorXKey {
+                        wildcard,
+                        ..xprv.clone()
+                    }),
+                    networks.clone(),
+                )
+            }
+            BdkDescriptorKey::Public(public, networks, _) if wildcard == Wildcard::None => {
+                BdkDescriptorKey::from_public(public.clone(), networks.clone())
+            }
+            BdkDescriptorKey::Secret(secret, networks, _) if wildcard == Wildcard::None => {
+                BdkDescriptorKey::from_secret(clone_secret_key(secret), networks.clone())
+            }
+            _ => return Err(Error::SingleKeyDerivation),
+        };
+        Ok(Self { descriptor_key })
+    }
+
+    /// Return the public counterpart of this key; public keys are returned unchanged.
+    ///
+    /// Secret keys with a hardened wildcard have no public counterpart.
+    pub fn as_public(&self) -> Result<Self, Error> {
+        let (descriptor_public_key, networks) = match &self.descriptor_key {
+            BdkDescriptorKey::Public(descriptor_public_key, networks, _) => {
+                (descriptor_public_key.clone(), networks)
+            }
+            BdkDescriptorKey::Secret(descriptor_secret_key, networks, _) => (
+                secret_key_as_public(secp(), descriptor_secret_key)?,
+                networks,
+            ),
+        };
+        Ok(Self {
+            descriptor_key: BdkDescriptorKey::from_public(descriptor_public_key, networks.clone()),
+        })
+    }
+
+    /// Return the master key fingerprint as 8 hex characters, taken from the key origin or,
+    /// for a master key without origin, from the key itself.
+    ///
+    /// Returns `None` for keys without origin that are not master keys.
+    pub fn master_fingerprint(&self) -> Option<String> {
+        let fingerprint = match &self.descriptor_key {
+            BdkDescriptorKey::Public(descriptor_public_key, _, _) => {
+                public_key_master_fingerprint(descriptor_public_key)
+            }
+            BdkDescriptorKey::Secret(descriptor_secret_key, _, _) => {
+                secret_key_master_fingerprint(secp(), descriptor_secret_key)
+            }
+        };
+        fingerprint.map(|f| f.to_string())
+    }
+
+    /// Return the key in descriptor notation.
+    pub fn as_string(&self) -> String {
+        match &self.descriptor_key {
+            BdkDescriptorKey::Public(descriptor_public_key, _, _) => {
+                descriptor_public_key.to_string()
+            }
+            BdkDescriptorKey::Secret(descriptor_secret_key, _, _) => {
+                descriptor_secret_key.to_string()
+            }
+        }
+    }
+
+    /// Whether this key holds secret material.
+    pub fn is_secret(&self) -> bool {
+        matches!(self.descriptor_key, BdkDescriptorKey::Secret(_, _, _))
+    }
+
+    /// Return the script context the key is meant for.
+    pub fn script_context(&self) -> ScriptContextEnum {
+        Ctx::as_enum()
+    }
+
+    /// Return this key for use in descriptors of the `Target` script context, such as
+    /// `DescriptorKey<Segwitv0>` for `wpkh` and `wsh` or `DescriptorKey<Tap>` for `tr`.
+    ///
+    /// Extended keys are valid in every context. Uncompressed single keys are only allowed in
+    /// legacy scripts and x-only public keys only in taproot scripts.
+    pub fn to_context<Target: ScriptContext + 'static>(
+        &self,
+    ) -> Result<DescriptorKey<Target>, Error> {
+        let descriptor_key = match &self.descriptor_key {
+            BdkDescriptorKey::Public(descriptor_public_key, networks, _) => {
+                BdkDescriptorKey::from_public(descriptor_public_key.clone(), networks.clone())
+            }
+            BdkDescriptorKey::Secret(descriptor_secret_key, networks, _) => {
+                BdkDescriptorKey::from_secret(
+                    clone_secret_key(descriptor_secret_key),
+                    networks.clone(),
+                )
+            }
+        };
+        check_context(&descriptor_key)?;
+        Ok(DescriptorKey { descriptor_key })
+    }
+}
+
+impl<Ctx: ScriptContext + 'static> Clone for DescriptorKey<Ctx> {
+    fn clone(&self) -> Self {
+        let descriptor_key = match &self.descriptor_key {
+            BdkDescriptorKey::Public(descriptor_public_key, networks, _) => {
+                BdkDescriptorKey::from_public(descriptor_public_key.clone(), networks.clone())
+            }
+            BdkDescriptorKey::Secret(descriptor_secret_key, networks, _) => {
+                BdkDescriptorKey::from_secret(
+                    clone_secret_key(descriptor_secret_key),
+                    networks.clone(),
+                )
+            }
+        };
+        Self { descriptor_key }
+    }
+}
+
+// The valid networks follow from the key, so the descriptor notation identifies it.
+impl<Ctx: ScriptContext + 'static> PartialEq for DescriptorKey<Ctx> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_string() == other.as_string()
+    }
+}
+
+impl<Ctx: ScriptContext + 'static> Eq for DescriptorKey<Ctx> {}
+
+impl<Ctx: ScriptContext + 'static> fmt::Debug for DescriptorKey<Ctx> {
+    /// Public keys are shown in descriptor notation; secret keys only by their master
+    /// fingerprint, so that logging a key never leaks it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("DescriptorKey");
+        if self.is_secret() {
+            debug
+                .field("secret", &"<redacted>")
+                .field("master_fingerprint", &self.master_fingerprint());
+        } else {
+            debug.field("public", &self.as_string());
+        }
+        debug.field("script_context", &Ctx::as_enum()).finish()
+    }
+}
+
+impl<Ctx: ScriptContext + 'static> Hash for DescriptorKey<Ctx> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_string().hash(state)
+    }
+}
+
+impl TryFrom<&crate::DescriptorSecretKey> for DescriptorKey {
+    type Error = Error;
+
+    fn try_from(key: &crate::DescriptorSecretKey) -> Result<Self, Error> {
+        let descriptor_key = clone_secret_key(&key.descriptor_secret_key).into_descriptor_key()?;
+        check_context(&descriptor_key)?;
+        Ok(Self { descriptor_key })
+    }
+}
+
+impl TryFrom<&crate::DescriptorPublicKey> for DescriptorKey {
+    type Error = Error;
+
+    fn try_from(key: &crate::DescriptorPublicKey) -> Result<Self, Error> {
+        let descriptor_key = key.descriptor_public_key.clone().into_descriptor_key()?;
+        check_context(&descriptor_key)?;
+        Ok(Self { descriptor_key })
+    }
+}
+
+/// Check that a single key is allowed in the script context of `descriptor_key`.
+fn check_context<Ctx: ScriptContext + 'static>(
+    descriptor_key: &BdkDescriptorKey<Ctx>,
+) -> Result<(), Error> {
+    let (compressed, x_only) = match descriptor_key {
+        BdkDescriptorKey::Public(DescriptorPublicKey::SinglePub(single), _, _) => {
+            match &single.key {
+                SinglePubKey::FullKey(key) => (key.compressed, false),
+                SinglePubKey::XOnly(_) => (true, true),
+            }
+        }
+        BdkDescriptorKey::Secret(DescriptorSecretKey::SinglePriv(single), _, _) => {
+            (single.key.compressed, false)
+        }
+        _ => return Ok(()),
+    };
+    let context = Ctx::as_enum();
+    if !compressed && !context.is_legacy() {
+        return Err(Error::InvalidScriptContext(format!(
+            "uncompressed keys are not allowed in {} scripts",
+            context_name(context)
+        )));
+    }
+    if x_only && !context.is_taproot() {
+        return Err(Error::InvalidScriptContext(format!(
+            "x-only keys are not allowed in {} scripts",
+            context_name(context)
+        )));
+    }
+    Ok(())
+}
+
+fn context_name(context: ScriptContextEnum) -> &'static str {
+    match context {
+        ScriptContextEnum::Legacy => "legacy",
+        ScriptContextEnum::Segwitv0 => "segwit v0",
+        ScriptContextEnum::Tap => "taproot",
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt;
+
+use bdk::bitcoin::util::bip32;
+use bdk::bitcoin::Network;
+use bdk::keys::bip39;
+use bdk::keys::KeyError;
+
+/// Errors returned by the descriptor key wrappers.
+#[derive(Debug)]
+pub enum Error {
+    /// The mnemonic could not be parsed.
+    InvalidMnemonic(bip39::Error),
+    /// Mnemonic input words at these zero-based positions did not resolve to a single word.
+    AmbiguousWords(Vec<usize>),
+    /// A master key fingerprint is not 8 hex characters.
+    InvalidFingerprint(String),
+    /// A mnemonic recovery template cannot be searched.
+    InvalidRecoveryTemplate(String),
+    /// A passphrase pattern cannot be searched.
+    InvalidPassphrasePattern(String),
+    /// User supplied entropy is malformed.
+    InvalidEntropy(String),
+    /// User supplied entropy has fewer bits than the mnemonic needs.
+    InsufficientEntropy {
+        /// The bits of entropy the mnemonic needs.
+        required: u32,
+        /// The bits of entropy in the input.
+        provided: u32,
+    },
+    /// A set of secret shares cannot be combined or split as requested.
+    InvalidShares(String),
+    /// A codex32 string is malformed or has an invalid checksum.
+    InvalidCodex32(String),
+    /// A BIP-85 application was asked for an unsupported length.
+    InvalidBip85(String),
+    /// An Electrum or aezeed seed phrase is malformed, unsupported or cannot be decrypted.
+    InvalidSeedPhrase(String),
+    /// A SeedQR payload is malformed or the mnemonic has no SeedQR.
+    InvalidSeedQr(String),
+    /// A recombined or recovered key does not have the expected master fingerprint.
+    FingerprintMismatch {
+        /// The fingerprint that was expected.
+        expected: String,
+        /// The fingerprint of the result.
+        found: String,
+    },
+    /// The derivation path could not be parsed.
+    InvalidDerivationPath(bip32::Error),
+    /// A hardened derivation step was requested on a public key.
+    HardenedDerivationFromPublic,
+    /// A child index was requested from a key without a wildcard.
+    NonRangedKey,
+    /// The key has a pending descriptor derivation path and deriving it was refused.
+    PendingDerivationPath,
+    /// The operation is not supported for this kind of key.
+    UnsupportedKeyKind,
+    /// Single (non-extended) keys cannot be derived or extended.
+    SingleKeyDerivation,
+    /// The key belongs to a different network than the one requested.
+    NetworkMismatch {
+        /// The network that was requested.
+        expected: Network,
+        /// The network of the key.
+        found: Network,
+    },
+    /// The key string is malformed.
+    InvalidKey(String),
+    /// A single key is not allowed in the script context it is used in.
+    InvalidScriptContext(String),
+    /// The key origin is malformed or inconsistent with the key.
+    InvalidOrigin(String),
+    /// Any other BIP32 error.
+    Bip32(bip32::Error),
+    /// An error raised by BDK while building a descriptor key.
+    Key(KeyError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidMnemonic(e) => write!(f, "invalid mnemonic: {}", e),
+            Error::AmbiguousWords(positions) => write!(
+                f,
+                "mnemonic words at positions {:?} are ambiguous or unknown",
+                positions
+            ),
+            Error::InvalidFingerprint(e) => write!(f, "invalid fingerprint: {}", e),
+            Error::InvalidRecoveryTemplate(e) => write!(f, "invalid recovery template: {}", e),
+            Error::InvalidPassphrasePattern(e) => write!(f, "invalid passphrase pattern: {}", e),
+            Error::InvalidEntropy(e) => write!(f, "invalid entropy: {}", e),
+            Error::InsufficientEntropy { required, provided } => write!(
+                f,
+                "entropy has {} bits but {} are required",
+                provided, required
+            ),
+            Error::InvalidShares(e) => write!(f, "invalid shares: {}", e),
+            Error::InvalidCodex32(e) => write!(f, "invalid codex32 string: {}", e),
+            Error::InvalidBip85(e) => write!(f, "invalid BIP-85 request: {}", e),
+            Error::InvalidSeedPhrase(e) => write!(f, "invalid seed phrase: {}", e),
+            Error::InvalidSeedQr(e) => write!(f, "invalid SeedQR: {}", e),
+            Error::FingerprintMismatch { expected, found } => write!(
+                f,
+                "master fingerprint is {} but {} was expected",
+                found, expected
+            ),
+            Error::InvalidDerivationPath(e) => write!(f, "invalid derivation path: {}", e),
+            Error::HardenedDerivationFromPublic => {
+                f.write_str("cannot derive a hardened step from a public key")
+            }
+            Error::NonRangedKey => f.write_str("key has no wildcard to derive an index from"),
+            Error::PendingDerivationPath => {
+                f.write_str("key has a pending derivation path that must be derived first")
+            }
+            Error::UnsupportedKeyKind => f.write_str("operation not supported for this key kind"),
+            Error::SingleKeyDerivation => f.write_str("single keys cannot be derived or extended"),
+            Error::NetworkMismatch { expected, found } => write!(
+                f,
+                "key is for network {} but {} was expected",
+                found, expected
+            ),
+            Error::InvalidKey(e) => write!(f, "invalid key: {}", e),
+            Error::InvalidScriptContext(e) => write!(f, "invalid key for script context: {}", e),
+            Error::InvalidOrigin(e) => write!(f, "invalid key origin: {}", e),
+            Error::Bip32(e) => write!(f, "BIP32 error: {}", e),
+            Error::Key(e) => write!(f, "key error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<bip39::Error> for Error {
+    fn from(e: bip39::Error) -> Self {
+        Error::InvalidMnemonic(e)
+    }
+}
+
+impl From<bip32::Error> for Error {
+    fn from(e: bip32::Error) -> Self {
+        match e {
+            bip32::Error::CannotDeriveFromHardenedKey => Error::HardenedDerivationFromPublic,
+            e => Error::Bip32(e),
+        }
+    }
+}
+
+impl From<KeyError> for Error {
+    fn from(e: KeyError) -> Self {
+        match e {
+            KeyError::Bip32(e) => e.into(),
+            e => Error::Key(e),
+        }
+    }
+}
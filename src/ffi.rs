@@ -0,0 +1,256 @@
+//! Shared handles for foreign language bindings.
+//!
+//! The key wrappers are plain values, but binding generators such as UniFFI pass objects
+//! across the boundary as `Arc` handles. Each type here wraps the value of the same name and
+//! takes and returns its object arguments as `Arc`, so bindings share one immutable instance
+//! instead of copying keys. Rust code should use the crate root types directly.
+
+use std::sync::Arc;
+
+use bdk::bitcoin::Network;
+use bdk::keys::bip39::WordCount;
+
+use crate::Error;
+
+/// Shared handle to a [`crate::Mnemonic`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mnemonic(crate::Mnemonic);
+
+impl Mnemonic {
+    /// See [`crate::Mnemonic::new`].
+    pub fn new(word_count: WordCount) -> Self {
+        Self(crate::Mnemonic::new(word_count))
+    }
+
+    /// See [`crate::Mnemonic::from_string`].
+    pub fn from_string(mnemonic: String) -> Result<Self, Error> {
+        crate::Mnemonic::from_string(mnemonic).map(Self)
+    }
+
+    /// See [`crate::Mnemonic::from_entropy`].
+    pub fn from_entropy(entropy: Vec<u8>) -> Result<Self, Error> {
+        crate::Mnemonic::from_entropy(entropy).map(Self)
+    }
+
+    /// See [`crate::Mnemonic::as_string`].
+    pub fn as_string(&self) -> String {
+        self.0.as_string()
+    }
+
+    /// Return the wrapped mnemonic.
+    pub fn value(&self) -> &crate::Mnemonic {
+        &self.0
+    }
+}
+
+impl From<crate::Mnemonic> for Mnemonic {
+    fn from(mnemonic: crate::Mnemonic) -> Self {
+        Self(mnemonic)
+    }
+}
+
+/// Shared handle to a [`crate::DerivationPath`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DerivationPath(crate::DerivationPath);
+
+impl DerivationPath {
+    /// See [`crate::DerivationPath::new`].
+    pub fn new(path: String) -> Result<Self, Error> {
+        crate::DerivationPath::new(path).map(Self)
+    }
+
+    /// Return the wrapped derivation path.
+    pub fn value(&self) -> &crate::DerivationPath {
+        &self.0
+    }
+}
+
+impl From<crate::DerivationPath> for DerivationPath {
+    fn from(path: crate::DerivationPath) -> Self {
+        Self(path)
+    }
+}
+
+/// Shared handle to a [`crate::DescriptorSecretKey`].
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct DescriptorSecretKey(crate::DescriptorSecretKey);
+
+impl DescriptorSecretKey {
+    /// See [`crate::DescriptorSecretKey::new`].
+    pub fn new(
+        network: Network,
+        mnemonic: Arc<Mnemonic>,
+        password: Option<String>,
+    ) -> Result<Self, Error> {
+        crate::DescriptorSecretKey::new(network, &mnemonic.0, password).map(Self)
+    }
+
+    /// See [`crate::DescriptorSecretKey::from_string`].
+    pub fn from_string(network: Network, key: String) -> Result<Self, Error> {
+        crate::DescriptorSecretKey::from_string(network, key).map(Self)
+    }
+
+    /// See [`crate::DescriptorSecretKey::derive`].
+    pub fn derive(&self, path: Arc<DerivationPath>) -> Result<Arc<Self>, Error> {
+        self.0.derive(&path.0).map(|key| Arc::new(Self(key)))
+    }
+
+    /// See [`crate::DescriptorSecretKey::extend`].
+    pub fn extend(&self, path: Arc<DerivationPath>) -> Result<Arc<Self>, Error> {
+        self.0.extend(&path.0).map(|key| Arc::new(Self(key)))
+    }
+
+    /// See [`crate::DescriptorSecretKey::at_index`].
+    pub fn at_index(&self, index: u32) -> Result<Arc<Self>, Error> {
+        self.0.at_index(index).map(|key| Arc::new(Self(key)))
+    }
+
+    /// See [`crate::DescriptorSecretKey::as_public`].
+    pub fn as_public(&self) -> Result<Arc<DescriptorPublicKey>, Error> {
+        self.0
+            .as_public()
+            .map(|key| Arc::new(DescriptorPublicKey(key)))
+    }
+
+    /// See [`crate::DescriptorSecretKey::master_fingerprint`].
+    pub fn master_fingerprint(&self) -> Option<String> {
+        self.0.master_fingerprint()
+    }
+
+    /// See [`crate::DescriptorSecretKey::as_string`].
+    pub fn as_string(&self) -> String {
+        self.0.as_string()
+    }
+
+    /// Return the wrapped key.
+    pub fn value(&self) -> &crate::DescriptorSecretKey {
+        &self.0
+    }
+}
+
+impl From<crate::DescriptorSecretKey> for DescriptorSecretKey {
+    fn from(key: crate::DescriptorSecretKey) -> Self {
+        Self(key)
+    }
+}
+
+/// Shared handle to a [`crate::DescriptorPublicKey`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DescriptorPublicKey(crate::DescriptorPublicKey);
+
+impl DescriptorPublicKey {
+    /// See [`crate::DescriptorPublicKey::from_string`].
+    pub fn from_string(network: Network, key: String) -> Result<Self, Error> {
+        crate::DescriptorPublicKey::from_string(network, key).map(Self)
+    }
+
+    /// See [`crate::DescriptorPublicKey::derive`].
+    pub fn derive(&self, path: Arc<DerivationPath>) -> Result<Arc<Self>, Error> {
+        self.0.derive(&path.0).map(|key| Arc::new(Self(key)))
+    }
+
+    /// See [`crate::DescriptorPublicKey::extend`].
+    pub fn extend(&self, path: Arc<DerivationPath>) -> Result<Arc<Self>, Error> {
+        self.0.extend(&path.0).map(|key| Arc::new(Self(key)))
+    }
+
+    /// See [`crate::DescriptorPublicKey::at_index`].
+    pub fn at_index(&self, index: u32) -> Result<Arc<Self>, Error> {
+        self.0.at_index(index).map(|key| Arc::new(Self(key)))
+    }
+
+    /// Derive the child keys at `start..end` on `threads` threads, as
+    /// [`crate::DescriptorPublicKey::derive_batch`] does, and return them all at once.
+    pub fn derive_batch(
+        &self,
+        start: u32,
+        end: u32,
+        threads: u32,
+    ) -> Result<Vec<Arc<Self>>, Error> {
+        self.0
+            .derive_batch(start..end, threads as usize)?
+            .map(|key| key.map(|key| Arc::new(Self(key))))
+            .collect()
+    }
+
+    /// See [`crate::DescriptorPublicKey::master_fingerprint`].
+    pub fn master_fingerprint(&self) -> Option<String> {
+        self.0.master_fingerprint()
+    }
+
+    /// See [`crate::DescriptorPublicKey::as_string`].
+    pub fn as_string(&self) -> String {
+        self.0.as_string()
+    }
+
+    /// Return the wrapped key.
+    pub fn value(&self) -> &crate::DescriptorPublicKey {
+        &self.0
+    }
+}
+
+impl From<crate::DescriptorPublicKey> for DescriptorPublicKey {
+    fn from(key: crate::DescriptorPublicKey) -> Self {
+        Self(key)
+    }
+}
+
+/// Shared handle to a [`crate::DescriptorKey`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DescriptorKey(crate::DescriptorKey);
+
+impl DescriptorKey {
+    /// See [`crate::DescriptorKey::new`].
+    pub fn new(
+        network: Network,
+        mnemonic: Arc<Mnemonic>,
+        password: Option<String>,
+    ) -> Result<Self, Error> {
+        crate::DescriptorKey::new(network, &mnemonic.0, password).map(Self)
+    }
+
+    /// See [`crate::DescriptorKey::from_string`].
+    pub fn from_string(network: Network, key: String) -> Result<Self, Error> {
+        crate::DescriptorKey::from_string(network, key).map(Self)
+    }
+
+    /// See [`crate::DescriptorKey::derive`].
+    pub fn derive(
+        &self,
+        origin_path: Option<Arc<DerivationPath>>,
+        descriptor_path: Option<Arc<DerivationPath>>,
+    ) -> Result<Arc<Self>, Error> {
+        self.0
+            .derive(
+                origin_path.as_ref().map(|path| &path.0),
+                descriptor_path.as_ref().map(|path| &path.0),
+            )
+            .map(|key| Arc::new(Self(key)))
+    }
+
+    /// See [`crate::DescriptorKey::as_public`].
+    pub fn as_public(&self) -> Result<Arc<Self>, Error> {
+        self.0.as_public().map(|key| Arc::new(Self(key)))
+    }
+
+    /// See [`crate::DescriptorKey::is_secret`].
+    pub fn is_secret(&self) -> bool {
+        self.0.is_secret()
+    }
+
+    /// See [`crate::DescriptorKey::as_string`].
+    pub fn as_string(&self) -> String {
+        self.0.as_string()
+    }
+
+    /// Return the wrapped key.
+    pub fn value(&self) -> &crate::DescriptorKey {
+        &self.0
+    }
+}
+
+impl From<crate::DescriptorKey> for DescriptorKey {
+    fn from(key: crate::DescriptorKey) -> Self {
+        Self(key)
+    }
+}
@@ -0,0 +1,790 @@
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::str::FromStr;
+
+use bdk::bitcoin::hashes::hex::FromHex;
+use bdk::bitcoin::secp256k1::{Secp256k1, Signing};
+use bdk::bitcoin::util::bip32::{
+    ChildNumber, DerivationPath as BdkDerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint,
+    KeySource,
+};
+use bdk::bitcoin::{Network, Script};
+use bdk::descriptor::{DescriptorXKey, Wildcard};
+use bdk::keys::{
+    DescriptorPublicKey as BdkDescriptorPublicKey, DescriptorSecretKey as BdkDescriptorSecretKey,
+};
+use bdk::miniscript::descriptor::DescriptorSinglePriv;
+
+use crate::secp::secp;
+use crate::{Codex32, DerivationPath, Error, Mnemonic, ScriptType, Slip39};
+
+/// What deriving does with the descriptor derivation path a key has from an earlier `extend`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PendingPathPolicy {
+    /// Derive the pending path before the requested one, moving it into the key origin.
+    ///
+    /// Extending with `m/0` and then deriving `m/1` yields the same key as deriving `m/0/1`.
+    #[default]
+    Fold,
+    /// Refuse to derive a key with a pending path and return
+    /// [`Error::PendingDerivationPath`].
+    Reject,
+}
+
+impl PendingPathPolicy {
+    /// Return the full path to derive for `path`, given the key's pending `derivation_path`.
+    pub(crate) fn apply(
+        self,
+        derivation_path: &BdkDerivationPath,
+        path: BdkDerivationPath,
+    ) -> Result<BdkDerivationPath, Error> {
+        match self {
+            _ if derivation_path.is_empty() => Ok(path),
+            PendingPathPolicy::Fold => Ok(derivation_path.extend(path)),
+            PendingPathPolicy::Reject => Err(Error::PendingDerivationPath),
+        }
+    }
+}
+
+/// A descriptor secret key: either an extended key (`xprv`) with optional origin, derivation
+/// path and wildcard, or a single WIF key with optional origin.
+///
+/// Keys are immutable values: every operation returns a new key, and a key can be cloned and
+/// shared between threads freely. Two keys are equal when their descriptor notation is.
+pub struct DescriptorSecretKey {
+    pub(crate) descriptor_secret_key: BdkDescriptorSecretKey,
+}
+
+impl DescriptorSecretKey {
+    /// Create a master key from a BIP39 mnemonic and an optional passphrase.
+    ///
+    /// The key has an unhardened wildcard; use [`DescriptorSecretKey::with_wildcard`] to change it.
+    pub fn new(
+        network: Network,
+        mnemonic: &Mnemonic,
+        password: Option<String>,
+    ) -> Result<Self, Error> {
+        Self::from_seed(network, mnemonic.seed(password))
+    }
+
+    /// Create a master key from raw BIP39 entropy of 16 to 32 bytes and an optional
+    /// passphrase, as [`DescriptorSecretKey::new`] does from the mnemonic encoding it.
+    pub fn from_entropy(
+        network: Network,
+        entropy: Vec<u8>,
+        password: Option<String>,
+    ) -> Result<Self, Error> {
+        Self::new(network, &Mnemonic::from_entropy(entropy)?, password)
+    }
+
+    /// Create a master key from a BIP32 seed of 16 to 64 bytes, such as the 64 byte seed of a
+    /// BIP39 mnemonic.
+    ///
+    /// The key has an unhardened wildcard; use [`DescriptorSecretKey::with_wildcard`] to change it.
+    pub fn from_seed(network: Network, seed: Vec<u8>) -> Result<Self, Error> {
+        Ok(Self {
+            descriptor_secret_key: BdkDescriptorSecretKey::XPrv(DescriptorXKey {
+                origin: None,
+                xkey: master_xprv(network, &seed)?,
+                derivation_path: BdkDerivationPath::master(),
+                wildcard: Wildcard::Unhardened,
+            }),
+        })
+    }
+
+    /// Create a master key from SLIP-39 `shares` and the passphrase they were made with, using
+    /// the recovered master secret as the BIP32 seed.
+    pub fn from_slip39(
+        network: Network,
+        shares: Vec<String>,
+        passphrase: Option<String>,
+    ) -> Result<Self, Error> {
+        Self::from_seed(network, Slip39::combine(shares, passphrase)?)
+    }
+
+    /// Create a master key from codex32 `shares`, or a single unshared codex32 string, using
+    /// the recovered master seed as the BIP32 seed.
+    pub fn from_codex32(network: Network, shares: Vec<String>) -> Result<Self, Error> {
+        let shares = shares
+            .into_iter()
+            .map(Codex32::from_string)
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_seed(network, Codex32::combine(shares)?.master_seed()?)
+    }
+
+    /// Parse a key in descriptor notation, such as `[d1d04177/84h/1h/0h]tprv.../0/*` or a WIF
+    /// key with an optional origin.
+    ///
+    /// The key must belong to `network`, and the origin path length of an extended key must match
+    /// its depth.
+    pub fn from_string(network: Network, key: String) -> Result<Self, Error> {
+        Ok(Self {
+            descriptor_secret_key: parse_secret_key(network, &key)?,
+        })
+    }
+
+    /// Derive a child key along `path`, recording the step in the key origin.
+    ///
+    /// A pending descriptor derivation path is derived first, as with
+    /// [`PendingPathPolicy::Fold`]. Single keys cannot be derived.
+    pub fn derive(&self, path: &DerivationPath) -> Result<Self, Error> {
+        self.derive_with_policy(path, PendingPathPolicy::default())
+    }
+
+    /// Derive a child key along `path`, handling a pending descriptor derivation path according
+    /// to `policy`.
+    pub fn derive_with_policy(
+        &self,
+        path: &DerivationPath,
+        policy: PendingPathPolicy,
+    ) -> Result<Self, Error> {
+        let descriptor_x_key = self.xprv()?;
+        let path = policy.apply(
+            &descriptor_x_key.derivation_path,
+            path.derivation_path.clone(),
+        )?;
+        let derived_xprv = descriptor_x_key.xkey.derive_priv(secp(), &path)?;
+        let key_source = extend_origin(
+            &descriptor_x_key.origin,
+            || descriptor_x_key.xkey.fingerprint(secp()),
+            &path,
+        );
+        Ok(Self {
+            descriptor_secret_key: BdkDescriptorSecretKey::XPrv(DescriptorXKey {
+                origin: Some(key_source),
+                xkey: derived_xprv,
+                derivation_path: BdkDerivationPath::default(),
+                wildcard: descriptor_x_key.wildcard,
+            }),
+        })
+    }
+
+    /// Append `path` to the descriptor derivation path without deriving the key.
+    ///
+    /// Single keys have no derivation path and cannot be extended.
+    pub fn extend(&self, path: &DerivationPath) -> Result<Self, Error> {
+        let descriptor_x_key = self.xprv()?;
+        Ok(Self {
+            descriptor_secret_key: BdkDescriptorSecretKey::XPrv(DescriptorXKey {
+                derivation_path: descriptor_x_key
+                    .derivation_path
+                    .extend(&path.derivation_path),
+                ..descriptor_x_key.clone()
+            }),
+        })
+    }
+
+    /// Return the definite child key at `index` of this ranged key.
+    ///
+    /// The wildcard is replaced by `index` (hardened for a hardened wildcard) and the whole
+    /// derivation path is derived, so the result carries its full origin and no wildcard.
+    pub fn at_index(&self, index: u32) -> Result<Self, Error> {
+        let descriptor_x_key = self.xprv()?;
+        let path = descriptor_x_key
+            .derivation_path
+            .child(wildcard_child(descriptor_x_key.wildcard, index)?);
+        Ok(Self {
+            descriptor_secret_key: BdkDescriptorSecretKey::XPrv(DescriptorXKey {
+                origin: Some(extend_origin(
+                    &descriptor_x_key.origin,
+                    || descriptor_x_key.xkey.fingerprint(secp()),
+                    &path,
+                )),
+                xkey: descriptor_x_key.xkey.derive_priv(secp(), &path)?,
+                derivation_path: BdkDerivationPath::default(),
+                wildcard: Wildcard::None,
+            }),
+        })
+    }
+
+    /// Iterate over the child keys at each index in `range`, as with
+    /// [`DescriptorSecretKey::at_index`].
+    pub fn iter_range(&self, range: Range<u32>) -> impl Iterator<Item = Result<Self, Error>> + '_ {
+        range.map(move |index| self.at_index(index))
+    }
+
+    /// Return the wildcard of this key; single keys have [`Wildcard::None`].
+    pub fn wildcard(&self) -> Wildcard {
+        match &self.descriptor_secret_key {
+            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => descriptor_x_key.wildcard,
+            BdkDescriptorSecretKey::SinglePriv(_) => Wildcard::None,
+        }
+    }
+
+    /// Return a copy of this key with `wildcard` as its wildcard.
+    ///
+    /// Single keys cannot be ranged, so they only accept [`Wildcard::None`].
+    pub fn with_wildcard(&self, wildcard: Wildcard) -> Result<Self, Error> {
+        let descriptor_secret_key = match &self.descriptor_secret_key {
+            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => {
+                BdkDescriptorSecretKey::XPrv(DescriptorXKey {
+                    wildcard,
+                    ..descriptor_x_key.clone()
+                })
+            }
+            single if wildcard == Wildcard::None => clone_secret_key(single),
+            BdkDescriptorSecretKey::SinglePriv(_) => return Err(Error::SingleKeyDerivation),
+        };
+        Ok(Self {
+            descriptor_secret_key,
+        })
+    }
+
+    /// Return the master key fingerprint as 8 hex characters, taken from the key origin or,
+    /// for a master key without origin, from the key itself.
+    ///
+    /// Returns `None` for keys without origin that are not master keys.
+    pub fn master_fingerprint(&self) -> Option<String> {
+        secret_key_master_fingerprint(secp(), &self.descriptor_secret_key).map(|f| f.to_string())
+    }
+
+    /// Return the public counterpart of this key.
+    ///
+    /// Keys with a hardened wildcard have no public counterpart.
+    pub fn as_public(&self) -> Result<DescriptorPublicKey, Error> {
+        Ok(DescriptorPublicKey {
+            descriptor_public_key: secret_key_as_public(secp(), &self.descriptor_secret_key)?,
+        })
+    }
+
+    /// Return the key in descriptor notation, e.g. `[d1d04177/0]tprv.../*`.
+    pub fn as_string(&self) -> String {
+        self.descriptor_secret_key.to_string()
+    }
+
+    /// The extended key, or an error for single keys, which cannot be derived.
+    pub(crate) fn xprv(&self) -> Result<&DescriptorXKey<ExtendedPrivKey>, Error> {
+        match &self.descriptor_secret_key {
+            BdkDescriptorSecretKey::XPrv(descriptor_x_key) => Ok(descriptor_x_key),
+            BdkDescriptorSecretKey::SinglePriv(_) => Err(Error::SingleKeyDerivation),
+        }
+    }
+}
+
+impl Clone for DescriptorSecretKey {
+    fn clone(&self) -> Self {
+        Self {
+            descriptor_secret_key: clone_secret_key(&self.descriptor_secret_key),
+        }
+    }
+}
+
+// miniscript does not compare or hash secret keys, so use their descriptor notation, which
+// holds every field.
+impl PartialEq for DescriptorSecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_string() == other.as_string()
+    }
+}
+
+impl Eq for DescriptorSecretKey {}
+
+impl Hash for DescriptorSecretKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_string().hash(state)
+    }
+}
+
+/// A descriptor public key: either an extended key (`xpub`) with optional origin, derivation
+/// path and wildcard, or a single hex public key with optional origin.
+///
+/// Like [`DescriptorSecretKey`], keys are immutable values that can be cloned, compared,
+/// hashed and shared between threads.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DescriptorPublicKey {
+    pub(crate) descriptor_public_key: BdkDescriptorPublicKey,
+}
+
+impl DescriptorPublicKey {
+    /// Parse a key in descriptor notation, such as `[d1d04177/84h/1h/0h]tpub.../0/*` or a hex
+    /// public key with an optional origin.
+    ///
+    /// Single public keys carry no network and are accepted on any network. Extended keys are
+    /// validated like [`DescriptorSecretKey::from_string`] and additionally may not contain
+    /// hardened steps after the key, since those cannot be derived from a public key.
+    pub fn from_string(network: Network, key: String) -> Result<Self, Error> {
+        Ok(Self {
+            descriptor_public_key: parse_public_key(network, &key)?,
+        })
+    }
+
+    /// Derive a child key along `path`, recording the step in the key origin.
+    ///
+    /// A pending descriptor derivation path is derived first, as with
+    /// [`PendingPathPolicy::Fold`]. Hardened steps cannot be derived from a public key, and
+    /// single keys cannot be derived.
+    pub fn derive(&self, path: &DerivationPath) -> Result<Self, Error> {
+        self.derive_with_policy(path, PendingPathPolicy::default())
+    }
+
+    /// Derive a child key along `path`, handling a pending descriptor derivation path according
+    /// to `policy`.
+    pub fn derive_with_policy(
+        &self,
+        path: &DerivationPath,
+        policy: PendingPathPolicy,
+    ) -> Result<Self, Error> {
+        let descriptor_x_key = self.xpub()?;
+        let path = policy.apply(
+            &descriptor_x_key.derivation_path,
+            path.derivation_path.clone(),
+        )?;
+        let derived_xpub = descriptor_x_key.xkey.derive_pub(secp(), &path)?;
+        let key_source = extend_origin(
+            &descriptor_x_key.origin,
+            || descriptor_x_key.xkey.fingerprint(),
+            &path,
+        );
+        Ok(Self {
+            descriptor_public_key: BdkDescriptorPublicKey::XPub(DescriptorXKey {
+                origin: Some(key_source),
+                xkey: derived_xpub,
+                derivation_path: BdkDerivationPath::default(),
+                wildcard: descriptor_x_key.wildcard,
+            }),
+        })
+    }
+
+    /// Append `path` to the descriptor derivation path without deriving the key.
+    ///
+    /// Hardened steps cannot be derived from a public key, and single keys have no derivation
+    /// path and cannot be extended.
+    pub fn extend(&self, path: &DerivationPath) -> Result<Self, Error> {
+        let descriptor_x_key = self.xpub()?;
+        check_unhardened(&path.derivation_path)?;
+        Ok(Self {
+            descriptor_public_key: BdkDescriptorPublicKey::XPub(DescriptorXKey {
+                derivation_path: descriptor_x_key
+                    .derivation_path
+                    .extend(&path.derivation_path),
+                ..descriptor_x_key.clone()
+            }),
+        })
+    }
+
+    /// Return the definite child key at `index` of this ranged key.
+    ///
+    /// The wildcard is replaced by `index` and the whole derivation path is derived, so the
+    /// result carries its full origin and no wildcard.
+    pub fn at_index(&self, index: u32) -> Result<Self, Error> {
+        let descriptor_x_key = self.xpub()?;
+        let path = descriptor_x_key
+            .derivation_path
+            .child(wildcard_child(descriptor_x_key.wildcard, index)?);
+        Ok(Self {
+            descriptor_public_key: BdkDescriptorPublicKey::XPub(DescriptorXKey {
+                origin: Some(extend_origin(
+                    &descriptor_x_key.origin,
+                    || descriptor_x_key.xkey.fingerprint(),
+                    &path,
+                )),
+                xkey: descriptor_x_key.xkey.derive_pub(secp(), &path)?,
+                derivation_path: BdkDerivationPath::default(),
+                wildcard: Wildcard::None,
+            }),
+        })
+    }
+
+    /// Iterate over the child keys at each index in `range`, as with
+    /// [`DescriptorPublicKey::at_index`].
+    pub fn iter_range(&self, range: Range<u32>) -> impl Iterator<Item = Result<Self, Error>> + '_ {
+        range.map(move |index| self.at_index(index))
+    }
+
+    /// Return the output script paying to this key with `script_type`.
+    ///
+    /// Ranged keys stand for many scripts; take their children with
+    /// [`DescriptorPublicKey::at_index`] or [`DescriptorPublicKey::derive_batch`] first.
+    /// Uncompressed keys have no P2WPKH script.
+    pub fn script_pubkey(&self, script_type: ScriptType) -> Result<Script, Error> {
+        let public_key = self
+            .descriptor_public_key
+            .derive_public_key(secp())
+            .map_err(|e| Error::InvalidKey(e.to_string()))?;
+        match script_type {
+            ScriptType::P2pkh => Ok(Script::new_p2pkh(&public_key.pubkey_hash())),
+            ScriptType::P2wpkh => public_key
+                .wpubkey_hash()
+                .map(|hash| Script::new_v0_p2wpkh(&hash))
+                .ok_or_else(|| {
+                    Error::InvalidScriptContext(
+                        "uncompressed keys are not allowed in segwit v0 scripts".to_string(),
+                    )
+                }),
+        }
+    }
+
+    /// Return the wildcard of this key; single keys have [`Wildcard::None`].
+    pub fn wildcard(&self) -> Wildcard {
+        match &self.descriptor_public_key {
+            BdkDescriptorPublicKey::XPub(descriptor_x_key) => descriptor_x_key.wildcard,
+            BdkDescriptorPublicKey::SinglePub(_) => Wildcard::None,
+        }
+    }
+
+    /// Return a copy of this key with `wildcard` as its wildcard.
+    ///
+    /// Hardened wildcards cannot be derived from a public key, and single keys only accept
+    /// [`Wildcard::None`].
+    pub fn with_wildcard(&self, wildcard: Wildcard) -> Result<Self, Error> {
+        if wildcard == Wildcard::Hardened {
+            return Err(Error::HardenedDerivationFromPublic);
+        }
+        let descriptor_public_key = match &self.descriptor_public_key {
+            BdkDescriptorPublicKey::XPub(descriptor_x_key) => {
+                BdkDescriptorPublicKey::XPub(DescriptorXKey {
+                    wildcard,
+                    ..descriptor_x_key.clone()
+                })
+            }
+            single if wildcard == Wildcard::None => single.clone(),
+            BdkDescriptorPublicKey::SinglePub(_) => return Err(Error::SingleKeyDerivation),
+        };
+        Ok(Self {
+            descriptor_public_key,
+        })
+    }
+
+    /// Return the master key fingerprint as 8 hex characters, taken from the key origin or,
+    /// for a master key without origin, from the key itself.
+    ///
+    /// Returns `None` for keys without origin that are not master keys.
+    pub fn master_fingerprint(&self) -> Option<String> {
+        public_key_master_fingerprint(&self.descriptor_public_key).map(|f| f.to_string())
+    }
+
+    /// Return the key in descriptor notation, e.g. `[d1d04177/0]tpub.../*`.
+    pub fn as_string(&self) -> String {
+        self.descriptor_public_key.to_string()
+    }
+
+    /// The extended key, or an error for single keys, which cannot be derived.
+    pub(crate) fn xpub(&self) -> Result<&DescriptorXKey<ExtendedPubKey>, Error> {
+        match &self.descriptor_public_key {
+            BdkDescriptorPublicKey::XPub(descriptor_x_key) => Ok(descriptor_x_key),
+            BdkDescriptorPublicKey::SinglePub(_) => Err(Error::SingleKeyDerivation),
+        }
+    }
+}
+
+/// Convert a secret key to its public counterpart, refusing hardened wildcards.
+pub(crate) fn secret_key_as_public<C: Signing>(
+    secp: &Secp256k1<C>,
+    descriptor_secret_key: &BdkDescriptorSecretKey,
+) -> Result<BdkDescriptorPublicKey, Error> {
+    if let BdkDescriptorSecretKey::XPrv(descriptor_x_key) = descriptor_secret_key {
+        if descriptor_x_key.wildcard == Wildcard::Hardened {
+            return Err(Error::HardenedDerivationFromPublic);
+        }
+    }
+    descriptor_secret_key
+        .as_public(secp)
+        .map_err(|e| Error::InvalidKey(e.to_string()))
+}
+
+/// Parse and validate a secret key in descriptor notation.
+pub(crate) fn parse_secret_key(
+    network: Network,
+    key: &str,
+) -> Result<BdkDescriptorSecretKey, Error> {
+    let mut descriptor_secret_key =
+        BdkDescriptorSecretKey::from_str(key).map_err(|e| Error::InvalidKey(e.to_string()))?;
+    match &mut descriptor_secret_key {
+        BdkDescriptorSecretKey::XPrv(xprv) => {
+            check_network(network, xprv.xkey.network)?;
+            check_origin(&xprv.origin, xprv.xkey.depth, || {
+                xprv.xkey.fingerprint(secp())
+            })?;
+        }
+        BdkDescriptorSecretKey::SinglePriv(single) => {
+            check_network(network, single.key.network)?;
+            // miniscript drops the origin of WIF keys while parsing
+            single.origin = parse_origin(key)?.0;
+        }
+    }
+    Ok(descriptor_secret_key)
+}
+
+/// Parse and validate a public key in descriptor notation.
+pub(crate) fn parse_public_key(
+    network: Network,
+    key: &str,
+) -> Result<BdkDescriptorPublicKey, Error> {
+    let descriptor_public_key =
+        BdkDescriptorPublicKey::from_str(key).map_err(|e| Error::InvalidKey(e.to_string()))?;
+    if let BdkDescriptorPublicKey::XPub(xpub) = &descriptor_public_key {
+        check_network(network, xpub.xkey.network)?;
+        check_origin(&xpub.origin, xpub.xkey.depth, || xpub.xkey.fingerprint())?;
+        if xpub.wildcard == Wildcard::Hardened {
+            return Err(Error::HardenedDerivationFromPublic);
+        }
+        check_unhardened(&xpub.derivation_path)?;
+    }
+    Ok(descriptor_public_key)
+}
+
+/// Refuse a descriptor derivation path with hardened steps, which a public key cannot derive.
+pub(crate) fn check_unhardened(path: &BdkDerivationPath) -> Result<(), Error> {
+    if path.into_iter().any(ChildNumber::is_hardened) {
+        return Err(Error::HardenedDerivationFromPublic);
+    }
+    Ok(())
+}
+
+/// Create the master extended key of a BIP32 `seed`.
+fn master_xprv(network: Network, seed: &[u8]) -> Result<ExtendedPrivKey, Error> {
+    if !(16..=64).contains(&seed.len()) {
+        return Err(Error::InvalidKey(format!(
+            "seed must be 16 to 64 bytes, got {}",
+            seed.len()
+        )));
+    }
+    Ok(ExtendedPrivKey::new_master(network, seed)?)
+}
+
+/// The master fingerprint of a secret key, from its origin or from a master key itself.
+pub(crate) fn secret_key_master_fingerprint<C: Signing>(
+    secp: &Secp256k1<C>,
+    descriptor_secret_key: &BdkDescriptorSecretKey,
+) -> Option<Fingerprint> {
+    match descriptor_secret_key {
+        BdkDescriptorSecretKey::XPrv(xprv) => match &xprv.origin {
+            Some((fingerprint, _)) => Some(*fingerprint),
+            None if xprv.xkey.depth == 0 => Some(xprv.xkey.fingerprint(secp)),
+            None => None,
+        },
+        BdkDescriptorSecretKey::SinglePriv(single) => {
+            single.origin.as_ref().map(|(fingerprint, _)| *fingerprint)
+        }
+    }
+}
+
+/// The master fingerprint of a public key, from its origin or from a master key itself.
+pub(crate) fn public_key_master_fingerprint(
+    descriptor_public_key: &BdkDescriptorPublicKey,
+) -> Option<Fingerprint> {
+    match descriptor_public_key {
+        BdkDescriptorPublicKey::XPub(xpub) => match &xpub.origin {
+            Some((fingerprint, _)) => Some(*fingerprint),
+            None if xpub.xkey.depth == 0 => Some(xpub.xkey.fingerprint()),
+            None => None,
+        },
+        BdkDescriptorPublicKey::SinglePub(single) => {
+            single.origin.as_ref().map(|(fingerprint, _)| *fingerprint)
+        }
+    }
+}
+
+/// The child number that replaces `wildcard` at `index`.
+fn wildcard_child(wildcard: Wildcard, index: u32) -> Result<ChildNumber, Error> {
+    match wildcard {
+        Wildcard::None => Err(Error::NonRangedKey),
+        Wildcard::Unhardened => Ok(ChildNumber::from_normal_idx(index)?),
+        Wildcard::Hardened => Ok(ChildNumber::from_hardened_idx(index)?),
+    }
+}
+
+/// Build the origin of a key derived along `path` from a key with `origin`.
+///
+/// The master fingerprint and origin path of an existing origin are kept and `path` is appended
+/// to them; a key without origin is treated as the master and `fingerprint` is used instead.
+pub(crate) fn extend_origin(
+    origin: &Option<KeySource>,
+    fingerprint: impl FnOnce() -> Fingerprint,
+    path: &BdkDerivationPath,
+) -> KeySource {
+    match origin {
+        Some((master_fingerprint, origin_path)) => (*master_fingerprint, origin_path.extend(path)),
+        None => (fingerprint(), path.clone()),
+    }
+}
+
+/// Check that a key origin is consistent with the extended key it describes.
+///
+/// The origin path must be as long as the key depth, and a master key can only have its own
+/// fingerprint as origin.
+fn check_origin(
+    origin: &Option<KeySource>,
+    depth: u8,
+    fingerprint: impl FnOnce() -> Fingerprint,
+) -> Result<(), Error> {
+    let (origin_fingerprint, origin_path) = match origin {
+        Some(origin) => origin,
+        None => return Ok(()),
+    };
+    if origin_path.len() != depth as usize {
+        return Err(Error::InvalidOrigin(format!(
+            "origin path has {} steps but the key has depth {}",
+            origin_path.len(),
+            depth
+        )));
+    }
+    if depth == 0 && *origin_fingerprint != fingerprint() {
+        return Err(Error::InvalidOrigin(
+            "master key origin does not match its own fingerprint".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Testnet, signet and regtest share their key serialization, so they are interchangeable here.
+pub(crate) fn check_network(expected: Network, found: Network) -> Result<(), Error> {
+    match (expected, found) {
+        (Network::Bitcoin, Network::Bitcoin) => Ok(()),
+        (Network::Bitcoin, _) | (_, Network::Bitcoin) => {
+            Err(Error::NetworkMismatch { expected, found })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Split a leading `[fingerprint/path]` key origin from `key`.
+fn parse_origin(key: &str) -> Result<(Option<KeySource>, &str), Error> {
+    let origin_and_key = match key.strip_prefix('[') {
+        Some(origin_and_key) => origin_and_key,
+        None => return Ok((None, key)),
+    };
+    let (origin, key) = origin_and_key
+        .split_once(']')
+        .ok_or_else(|| Error::InvalidOrigin("unclosed '['".to_string()))?;
+    let mut origin = origin.split('/');
+    let fingerprint = origin
+        .next()
+        .filter(|fingerprint| fingerprint.len() == 8)
+        .and_then(|fingerprint| Fingerprint::from_hex(fingerprint).ok())
+        .ok_or_else(|| Error::InvalidOrigin("malformed master fingerprint".to_string()))?;
+    let origin_path = origin
+        .map(ChildNumber::from_str)
+        .collect::<Result<BdkDerivationPath, _>>()
+        .map_err(Error::InvalidDerivationPath)?;
+    Ok((Some((fingerprint, origin_path)), key))
+}
+
+/// `BdkDescriptorSecretKey` does not implement `Clone`, so copy it field by field.
+pub(crate) fn clone_secret_key(key: &BdkDescriptorSecretKey) -> BdkDescriptorSecretKey {
+    match key {
+        BdkDescriptorSecretKey::SinglePriv(single) => {
+            BdkDescriptorSecretKey::SinglePriv(DescriptorSinglePriv {
+                origin: single.origin.clone(),
+                key: single.key,
+            })
+        }
+        BdkDescriptorSecretKey::XPrv(xprv) => BdkDescriptorSecretKey::XPrv(xprv.clone()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use bdk::bitcoin::util::bip32::DerivationPath as BdkDerivationPath;
+    use bdk::bitcoin::Network;
+    use bdk::keys::DescriptorSecretKey as BdkDescriptorSecretKey;
+
+    use crate::{DerivationPath, DescriptorPublicKey, DescriptorSecretKey, Error, Mnemonic};
+
+    fn get_descriptor_secret_key() -> DescriptorSecretKey {
+        let mnemonic =
+        "chaos fabric time speed sponsor all flat solution wisdom trophy crack object robot pave observe combine where aware bench orient secret primary cable detect".to_string();
+        let mnemonic = Mnemonic::from_string(mnemonic).unwrap();
+        DescriptorSecretKey::new(Network::Testnet, &mnemonic, None).unwrap()
+    }
+
+    fn derive_dsk(key: &DescriptorSecretKey, path: &str) -> Result<DescriptorSecretKey, Error> {
+        let path = DerivationPath::new(path.to_string()).unwrap();
+        key.derive(&path)
+    }
+
+    fn extend_dsk(key: &DescriptorSecretKey, path: &str) -> Result<DescriptorSecretKey, Error> {
+        let path = DerivationPath::new(path.to_string()).unwrap();
+        key.extend(&path)
+    }
+
+    fn derive_dpk(key: &DescriptorPublicKey, path: &str) -> Result<DescriptorPublicKey, Error> {
+        let path = DerivationPath::new(path.to_string()).unwrap();
+        key.derive(&path)
+    }
+
+    fn extend_dpk(key: &DescriptorPublicKey, path: &str) -> Result<DescriptorPublicKey, Error> {
+        let path = DerivationPath::new(path.to_string()).unwrap();
+        key.extend(&path)
+    }
+
+    #[test]
+    fn test_generate_descriptor_secret_key() {
+        let master_dsk = get_descriptor_secret_key();
+        assert_eq!(master_dsk.as_string(), "tprv8ZgxMBicQKsPdWuqM1t1CDRvQtQuBPyfL6GbhQwtxDKgUAVPbxmj71pRA8raTqLrec5LyTs5TqCxdABcZr77bt2KyWA5bizJHnC4g4ysm4h/*");
+        assert_eq!(master_dsk.as_public().unwrap().as_string(), "tpubD6NzVbkrYhZ4WywdEfYbbd62yuvqLjAZuPsNyvzCNV85JekAEMbKHWSHLF9h3j45SxewXDcLv328B1SEZrxg4iwGfmdt1pDFjZiTkGiFqGa/*");
+    }
+
+    #[test]
+    fn test_derive_self() {
+        let master_dsk = get_descriptor_secret_key();
+        let derived_dsk: &DescriptorSecretKey = &derive_dsk(&master_dsk, "m").unwrap();
+        assert_eq!(derived_dsk.as_string(), "[d1d04177]tprv8ZgxMBicQKsPdWuqM1t1CDRvQtQuBPyfL6GbhQwtxDKgUAVPbxmj71pRA8raTqLrec5LyTs5TqCxdABcZr77bt2KyWA5bizJHnC4g4ysm4h/*");
+
+        let master_dpk: &DescriptorPublicKey = &master_dsk.as_public().unwrap();
+        let derived_dpk: &DescriptorPublicKey = &derive_dpk(master_dpk, "m").unwrap();
+        assert_eq!(derived_dpk.as_string(), "[d1d04177]tpubD6NzVbkrYhZ4WywdEfYbbd62yuvqLjAZuPsNyvzCNV85JekAEMbKHWSHLF9h3j45SxewXDcLv328B1SEZrxg4iwGfmdt1pDFjZiTkGiFqGa/*");
+    }
+
+    #[test]
+    fn test_derive_descriptors_keys() {
+        let master_dsk = get_descriptor_secret_key();
+        let derived_dsk: &DescriptorSecretKey = &derive_dsk(&master_dsk, "m/0").unwrap();
+        assert_eq!(derived_dsk.as_string(), "[d1d04177/0]tprv8d7Y4JLmD25jkKbyDZXcdoPHu1YtMHuH21qeN7mFpjfumtSU7eZimFYUCSa3MYzkEYfSNRBV34GEr2QXwZCMYRZ7M1g6PUtiLhbJhBZEGYJ/*");
+
+        let master_dpk: &DescriptorPublicKey = &master_dsk.as_public().unwrap();
+        let derived_dpk: &DescriptorPublicKey = &derive_dpk(master_dpk, "m/0").unwrap();
+        assert_eq!(derived_dpk.as_string(), "[d1d04177/0]tpubD9oaCiP1MPmQdndm7DCD3D3QU34pWd6BbKSRedoZF1UJcNhEk3PJwkALNYkhxeTKL29oGNR7psqvT1KZydCGqUDEKXN6dVQJY2R8ooLPy8m/*");
+    }
+
+    #[test]
+    fn test_extend_descriptor_keys() {
+        let master_dsk = get_descriptor_secret_key();
+        let extended_dsk: &DescriptorSecretKey = &extend_dsk(&master_dsk, "m/0").unwrap();
+        assert_eq!(extended_dsk.as_string(), "tprv8ZgxMBicQKsPdWuqM1t1CDRvQtQuBPyfL6GbhQwtxDKgUAVPbxmj71pRA8raTqLrec5LyTs5TqCxdABcZr77bt2KyWA5bizJHnC4g4ysm4h/0/*");
+
+        let master_dpk: &DescriptorPublicKey = &master_dsk.as_public().unwrap();
+        let extended_dpk: &DescriptorPublicKey = &extend_dpk(master_dpk, "m/0").unwrap();
+        assert_eq!(extended_dpk.as_string(), "tpubD6NzVbkrYhZ4WywdEfYbbd62yuvqLjAZuPsNyvzCNV85JekAEMbKHWSHLF9h3j45SxewXDcLv328B1SEZrxg4iwGfmdt1pDFjZiTkGiFqGa/0/*");
+    }
+
+    #[test]
+    fn test_derive_and_extend_descriptor_secret_key() {
+        let master_dsk = get_descriptor_secret_key();
+
+        // derive DescriptorSecretKey with path "m/0" from master
+        let derived_dsk: &DescriptorSecretKey = &derive_dsk(&master_dsk, "m/0").unwrap();
+        assert_eq!(derived_dsk.as_string(), "[d1d04177/0]tprv8d7Y4JLmD25jkKbyDZXcdoPHu1YtMHuH21qeN7mFpjfumtSU7eZimFYUCSa3MYzkEYfSNRBV34GEr2QXwZCMYRZ7M1g6PUtiLhbJhBZEGYJ/*");
+
+        // extend derived_dsk with path "m/0"
+        let extended_dsk: &DescriptorSecretKey = &extend_dsk(derived_dsk, "m/0").unwrap();
+        assert_eq!(extended_dsk.as_string(), "[d1d04177/0]tprv8d7Y4JLmD25jkKbyDZXcdoPHu1YtMHuH21qeN7mFpjfumtSU7eZimFYUCSa3MYzkEYfSNRBV34GEr2QXwZCMYRZ7M1g6PUtiLhbJhBZEGYJ/0/*");
+
+        // checking extended derivation_path
+        assert_eq!(
+            match &extended_dsk.descriptor_secret_key {
+                BdkDescriptorSecretKey::XPrv(xkey) => {
+                    Some(xkey.derivation_path.clone())
+                }
+                _ => {
+                    None
+                }
+            }
+            .unwrap()
+            .to_string(),
+            BdkDerivationPath::from_str("m/0").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_derive_hardened_path_using_public() {
+        let master_dpk = get_descriptor_secret_key().as_public().unwrap();
+        let derived_dpk = derive_dpk(&master_dpk, "m/84h/1h/0h");
+        assert!(matches!(
+            derived_dpk,
+            Err(Error::HardenedDerivationFromPublic)
+        ));
+    }
+}
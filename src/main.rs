@@ -1,21 +1,13 @@
-use std::ops::Deref;
+use std::num::ParseIntError;
+use std::process;
 use std::str::FromStr;
-use std::sync::{Arc, Mutex};
-
-use bdk::bitcoin::secp256k1::Secp256k1;
-use bdk::bitcoin::util::bip32::{DerivationPath as BdkDerivationPath, KeySource};
-use bdk::bitcoin::Network;
-use bdk::descriptor::Legacy;
-use bdk::keys::bip39::{Language, Mnemonic, WordCount};
-use bdk::keys::{
-    DerivableKey, DescriptorKey as BdkDescriptorKey, DescriptorPublicKey, DescriptorSecretKey,
-    ExtendedKey, GeneratableKey, GeneratedKey,
-};
-use bdk::miniscript::BareCtx;
-use bdk::Error as BdkError;
 
-#[allow(unused_imports)]
-use extended_keys_derive_rust::lib_main;
+use bdk::bitcoin::hashes::hex::ToHex;
+use extended_keys_derive_rust::{
+    DerivationPath, DescriptorKey, DescriptorSecretKey, EntropySource, Language, Mnemonic,
+    MnemonicRecovery, Network, PassphrasePattern, PassphraseRecovery, PassphraseSearch,
+    PassphraseTarget, Segwitv0, UserEntropy, WordCount,
+};
 
 /*
 
@@ -28,208 +20,306 @@ use extended_keys_derive_rust::lib_main;
 
 */
 
+const MNEMONIC: &str = "chaos fabric time speed sponsor all flat solution wisdom trophy crack object robot pave observe combine where aware bench orient secret primary cable detect";
+
+fn mnemonic() -> Mnemonic {
+    Mnemonic::from_string(MNEMONIC.to_string()).unwrap()
+}
+
+const RECOVER_USAGE: &str = "usage: recover \"<words, ? for each missing word>\" [--fingerprint <hex>] [--passphrase <passphrase>] [--language <language>]";
+
+const GENERATE_USAGE: &str = "usage: generate [--words <12|15|18|21|24>] [--language <language>] [--d6 <rolls> | --d20 <rolls> | --cards <cards> | --coins <flips> | --hex <hex>] [--mix-os]";
+
+const RECOVER_PASSPHRASE_USAGE: &str = "usage: recover-passphrase \"<mnemonic>\" (--fingerprint <hex> | --xpub <xpub> --path <path>) [--words <a,b,...>] [--casing <a,b,...>] [--digits <n>] [--resume <index>]";
+
 fn main() {
-    this_main();
-    // lib_main();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("generate") => generate_main(&args[1..]),
+        Some("recover") => recover_main(&args[1..]),
+        Some("recover-passphrase") => recover_passphrase_main(&args[1..]),
+        _ => {
+            descriptor_key_main();
+            descriptor_secret_key_main();
+        }
+    }
 }
 
-#[allow(dead_code)]
-fn this_main() {
+fn recover_main(args: &[String]) {
+    let mut template = None;
+    let mut fingerprint = None;
+    let mut passphrase = None;
+    let mut language = Language::English;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fingerprint" => fingerprint = Some(flag_value(args.next(), RECOVER_USAGE)),
+            "--passphrase" => passphrase = Some(flag_value(args.next(), RECOVER_USAGE)),
+            "--language" => language = parse_language(&flag_value(args.next(), RECOVER_USAGE)),
+            _ if template.is_none() => template = Some(arg.clone()),
+            _ => fail(RECOVER_USAGE),
+        }
+    }
+    let template = template.unwrap_or_else(|| fail(RECOVER_USAGE));
+
+    let recovery = MnemonicRecovery::new(language, template, fingerprint, passphrase)
+        .unwrap_or_else(|e| fail(&e.to_string()));
+    eprintln!("searching {} candidates", recovery.candidate_count());
+    let found = recovery.recover();
+    eprintln!("{} matching mnemonics", found.len());
+    for mnemonic in found {
+        println!("{}", mnemonic.as_string());
+    }
+}
+
+fn recover_passphrase_main(args: &[String]) {
+    let mut mnemonic = None;
+    let mut fingerprint = None;
+    let mut xpub = None;
+    let mut derivation_path = None;
+    let mut pattern = PassphrasePattern::new();
+    let mut resume_from = 0;
+
+    let value = |value: Option<&String>| flag_value(value, RECOVER_PASSPHRASE_USAGE);
+    let list = |value: Option<&String>| -> Vec<String> {
+        flag_value(value, RECOVER_PASSPHRASE_USAGE)
+            .split(',')
+            .map(str::to_string)
+            .collect()
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fingerprint" => fingerprint = Some(value(args.next())),
+            "--xpub" => xpub = Some(value(args.next())),
+            "--path" => derivation_path = Some(value(args.next())),
+            "--words" => pattern = pattern.with_words(list(args.next())),
+            "--casing" => pattern = pattern.with_casing_variants(list(args.next())),
+            "--digits" => pattern = pattern.with_digits(number(&value(args.next()))),
+            "--resume" => resume_from = number(&value(args.next())),
+            _ if mnemonic.is_none() => mnemonic = Some(arg.clone()),
+            _ => fail(RECOVER_PASSPHRASE_USAGE),
+        }
+    }
+
+    let mnemonic = mnemonic.unwrap_or_else(|| fail(RECOVER_PASSPHRASE_USAGE));
+    let mnemonic = Mnemonic::from_string(mnemonic).unwrap_or_else(|e| fail(&e.to_string()));
+    let target = match (fingerprint, xpub, derivation_path) {
+        (Some(fingerprint), None, None) => PassphraseTarget::Fingerprint(fingerprint),
+        (None, Some(xpub), Some(derivation_path)) => PassphraseTarget::Xpub {
+            xpub,
+            path: DerivationPath::new(derivation_path).unwrap_or_else(|e| fail(&e.to_string())),
+        },
+        _ => fail(RECOVER_PASSPHRASE_USAGE),
+    };
+
+    let recovery = PassphraseRecovery::new(&mnemonic, target, pattern)
+        .unwrap_or_else(|e| fail(&e.to_string()));
+    let search = recovery.recover(resume_from, |progress| {
+        eprint!(
+            "\rchecked {}/{} (resume with --resume {})",
+            progress.checked, progress.total, progress.checked
+        );
+        true
+    });
+    eprintln!();
+    match search {
+        PassphraseSearch::Found { passphrase, index } => {
+            eprintln!("found at candidate {}", index);
+            println!("{}", passphrase);
+        }
+        PassphraseSearch::Exhausted => fail("no candidate matched"),
+        PassphraseSearch::Stopped { resume_from } => {
+            fail(&format!("stopped, resume with --resume {}", resume_from))
+        }
+    }
+}
+
+fn generate_main(args: &[String]) {
+    let mut words = "24".to_string();
+    let mut language = Language::English;
+    let mut entropy = None;
+    let mut mix_os = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let source = match arg.as_str() {
+            "--words" => {
+                words = flag_value(args.next(), GENERATE_USAGE);
+                word_count(&words);
+                continue;
+            }
+            "--language" => {
+                language = parse_language(&flag_value(args.next(), GENERATE_USAGE));
+                continue;
+            }
+            "--mix-os" => {
+                mix_os = true;
+                continue;
+            }
+            "--d6" => EntropySource::D6,
+            "--d20" => EntropySource::D20,
+            "--cards" => EntropySource::Cards,
+            "--coins" => EntropySource::CoinFlips,
+            "--hex" => EntropySource::Hex,
+            _ => fail(GENERATE_USAGE),
+        };
+        if entropy.is_some() {
+            fail(GENERATE_USAGE);
+        }
+        entropy = Some(
+            UserEntropy::new(source, flag_value(args.next(), GENERATE_USAGE))
+                .unwrap_or_else(|e| fail(&e.to_string())),
+        );
+    }
+
+    let entropy = match entropy {
+        Some(entropy) => entropy,
+        None => {
+            println!(
+                "{}",
+                Mnemonic::new_in(language, word_count(&words)).as_string()
+            );
+            return;
+        }
+    };
+    eprintln!("canonical input: {}", entropy.canonical());
+    eprintln!("entropy bits: {}", entropy.bits());
+    let mix = mix_os.then(|| UserEntropy::os_randomness(word_count(&words)));
+    if let Some(mix) = &mix {
+        eprintln!("mixed in OS randomness: {}", mix.to_hex());
+    }
+    let mnemonic = entropy
+        .to_mnemonic(language, word_count(&words), mix)
+        .unwrap_or_else(|e| fail(&e.to_string()));
+    println!("{}", mnemonic.as_string());
+}
+
+fn word_count(words: &str) -> WordCount {
+    match words {
+        "12" => WordCount::Words12,
+        "15" => WordCount::Words15,
+        "18" => WordCount::Words18,
+        "21" => WordCount::Words21,
+        "24" => WordCount::Words24,
+        words => fail(&format!("{} is not a mnemonic word count", words)),
+    }
+}
+
+fn parse_language(name: &str) -> Language {
+    *Language::all()
+        .iter()
+        .find(|language| language.to_string().eq_ignore_ascii_case(name))
+        .unwrap_or_else(|| fail(&format!("unknown language {}", name)))
+}
+
+fn flag_value(value: Option<&String>, usage: &str) -> String {
+    value.cloned().unwrap_or_else(|| fail(usage))
+}
+
+fn number<T: FromStr<Err = ParseIntError>>(value: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|e| fail(&format!("{} is not a valid number: {}", value, e)))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
+
+fn descriptor_key_main() {
     println!("\nDescriptorKey\n\n");
     // master
-    let mnemonic =
-        "chaos fabric time speed sponsor all flat solution wisdom trophy crack object robot pave observe combine where aware bench orient secret primary cable detect".to_string();
-    let master_des = DescriptorKey::new(Network::Testnet, mnemonic, None).unwrap();
-    show_descriptor_key_data(&master_des, "master");
+    let master_des = DescriptorKey::new(Network::Testnet, &mnemonic(), None).unwrap();
+    show_key(&master_des.as_string(), "master");
 
     // derive m/0 from master
     let derived_des: &DescriptorKey = &derive_key(&master_des, "m/0");
-    show_descriptor_key_data(derived_des, "derive m/0 from master");
+    show_key(&derived_des.as_string(), "derive m/0 from master");
 
     // extend m/0 from master
     let extended_des = &extend_key(&master_des, "m/0");
-    show_descriptor_key_data(extended_des, "extend m/0 from master");
+    show_key(&extended_des.as_string(), "extend m/0 from master");
 
     // derive m/0 and extend m/0 from master
     let extend_derived_des: &DescriptorKey = &extend_key(derived_des, "m/0");
-    show_descriptor_key_data(extend_derived_des, "derive m/0 and extend m/0");
+    show_key(&extend_derived_des.as_string(), "derive m/0 and extend m/0");
 
     // extend m/0 and extend m/0 from master
     let extend_extended_des: &DescriptorKey = &extend_key(extended_des, "m/0");
-    show_descriptor_key_data(extend_extended_des, "extend m/0 and extend m/0");
+    show_key(
+        &extend_extended_des.as_string(),
+        "extend m/0 and extend m/0",
+    );
 
     // extend m/0 and derive m/0 from master
     let derive_extended_des = &derive_key(extended_des, "m/0");
-    show_descriptor_key_data(derive_extended_des, "extend m/0 and derive m/0");
+    show_key(
+        &derive_extended_des.as_string(),
+        "extend m/0 and derive m/0",
+    );
 
     let derive_hardened_des = &derive_key(&master_des, "m/84h/1h/0h");
-    show_descriptor_key_data(derive_hardened_des, "derive m/84h/1h/0h from master")
-}
+    show_key(
+        &derive_hardened_des.as_string(),
+        "derive m/84h/1h/0h from master",
+    );
 
-#[allow(dead_code)]
-fn derive_key(key: &DescriptorKey, path: &str) -> Arc<DescriptorKey> {
-    let path = Arc::new(DerivationPath::new(path.to_string()).unwrap());
-    key.derive(Some(path), None).unwrap()
+    // use m/84h/1h/0h in a segwit v0 descriptor
+    let segwit_des = derive_hardened_des.to_context::<Segwitv0>().unwrap();
+    show_key(&segwit_des.as_string(), "m/84h/1h/0h for segwit v0")
 }
 
-#[allow(dead_code)]
-fn extend_key(key: &DescriptorKey, path: &str) -> Arc<DescriptorKey> {
-    let path = Arc::new(DerivationPath::new(path.to_string()).unwrap());
-    key.derive(None, Some(path)).unwrap()
-}
+fn descriptor_secret_key_main() {
+    println!("\nDescriptorSecretKey\n\n");
+    // master
+    let master_des = DescriptorSecretKey::new(Network::Testnet, &mnemonic(), None).unwrap();
+    show_key(&master_des.as_string(), "master");
 
-#[allow(dead_code)]
-fn show_descriptor_key_data(descriptor_key: &DescriptorKey, message: &str) {
-    println!("{}", message);
-    let key = descriptor_key.descriptor_key_mutex.lock().unwrap();
-    match key.deref() {
-        BdkDescriptorKey::Secret(key, _, _) => {
-            println!("{}", key)
-        }
-        BdkDescriptorKey::Public(key, _, _) => {
-            println!("{}", key)
-        }
-    }
-    match key.deref() {
-        BdkDescriptorKey::Secret(DescriptorSecretKey::XPrv(xprv), _, _) => {
-            println!("xkey            -> {}", xprv.xkey);
-            println!("origin          -> {:?}", xprv.origin);
-            println!("derivation_path -> {:?}", xprv.derivation_path);
-            println!("wildcard        -> {:?}", xprv.wildcard);
-        }
-        BdkDescriptorKey::Public(DescriptorPublicKey::XPub(xpub), _, _) => {
-            println!("xkey            -> {}", xpub.xkey);
-            println!("origin          -> {:?}", xpub.origin);
-            println!("derivation_path -> {:?}", xpub.derivation_path);
-            println!("wildcard        -> {:?}", xpub.wildcard);
-        }
-        _ => todo!(),
-    }
-    println!("================================")
-}
+    // derive m/0 from master
+    let derived_des: &DescriptorSecretKey = &master_des.derive(&path("m/0")).unwrap();
+    show_key(&derived_des.as_string(), "derive m/0 from master");
 
-#[allow(dead_code)]
-fn generate_mnemonic(word_count: WordCount) -> Result<String, BdkError> {
-    let mnemonic: GeneratedKey<_, BareCtx> =
-        Mnemonic::generate((word_count, Language::English)).unwrap();
-    Ok(mnemonic.to_string())
-}
+    // extend m/0 from master
+    let extended_des: &DescriptorSecretKey = &master_des.extend(&path("m/0")).unwrap();
+    show_key(&extended_des.as_string(), "extend m/0 from master");
 
-#[allow(dead_code)]
-#[derive(Debug)]
-struct DerivationPath {
-    derivation_path_mutex: Mutex<BdkDerivationPath>,
-}
+    // derive m/0 and extend m/0 from master
+    let extend_derived_des = derived_des.extend(&path("m/0")).unwrap();
+    show_key(&extend_derived_des.as_string(), "derive m/0 and extend m/0");
 
-#[allow(dead_code)]
-impl DerivationPath {
-    fn new(path: String) -> Result<Self, BdkError> {
-        BdkDerivationPath::from_str(&path)
-            .map(|x| DerivationPath {
-                derivation_path_mutex: Mutex::new(x),
-            })
-            .map_err(|e| BdkError::Generic(e.to_string()))
-    }
+    // extend m/0 and extend m/0 from master
+    let extend_extended_des = extended_des.extend(&path("m/0")).unwrap();
+    show_key(
+        &extend_extended_des.as_string(),
+        "extend m/0 and extend m/0",
+    );
+
+    // extend m/0 and derive m/0 from master
+    let derive_extended_des = extended_des.derive(&path("m/0")).unwrap();
+    show_key(
+        &derive_extended_des.as_string(),
+        "extend m/0 and derive m/0",
+    );
 }
 
-#[allow(dead_code)]
-#[derive(Debug)]
-struct DescriptorKey {
-    descriptor_key_mutex: Mutex<BdkDescriptorKey<Legacy>>,
-}
-
-#[allow(dead_code)]
-impl DescriptorKey {
-    fn new(network: Network, mnemonic: String, password: Option<String>) -> Result<Self, BdkError> {
-        let mnemonic = Mnemonic::parse_in(Language::English, mnemonic)
-            .map_err(|e| BdkError::Generic(e.to_string()))?;
-        let xkey: ExtendedKey = (mnemonic, password).into_extended_key()?;
-        let descriptor_key = xkey
-            .into_xprv(network)
-            .unwrap()
-            .into_descriptor_key(None, BdkDerivationPath::master())?;
-        Ok(Self {
-            descriptor_key_mutex: Mutex::new(descriptor_key),
-        })
-    }
+fn path(path: &str) -> DerivationPath {
+    DerivationPath::new(path.to_string()).unwrap()
+}
 
-    fn derive(
-        &self,
-        origin_path: Option<Arc<DerivationPath>>,
-        descriptor_path: Option<Arc<DerivationPath>>,
-    ) -> Result<Arc<DescriptorKey>, BdkError> {
-        let secp = Secp256k1::new();
-        let root_key = self.descriptor_key_mutex.lock().unwrap();
-        let root_path =
-            origin_path.map(|op| op.derivation_path_mutex.lock().unwrap().deref().clone());
-        let descriptor_path = descriptor_path
-            .map(|dp| dp.derivation_path_mutex.lock().unwrap().deref().clone())
-            .unwrap_or_default();
-        match root_key.deref() {
-            BdkDescriptorKey::Public(DescriptorPublicKey::XPub(xpub), _, _) => {
-                let derived_descriptor_key = if let Some(path) = root_path {
-                    let key_source: KeySource = (xpub.xkey.fingerprint(), path.clone());
-                    let derived_xpub = xpub.xkey.derive_pub(&secp, &path)?;
-                    derived_xpub.into_descriptor_key(Some(key_source), descriptor_path)?
-                } else {
-                    xpub.xkey
-                        .into_descriptor_key(xpub.origin.clone(), descriptor_path)?
-                };
-                Ok(Arc::new(DescriptorKey {
-                    descriptor_key_mutex: Mutex::new(derived_descriptor_key),
-                }))
-            }
-            BdkDescriptorKey::Secret(DescriptorSecretKey::XPrv(xprv), _, _) => {
-                let derived_descriptor_key = if let Some(path) = root_path {
-                    let key_source: KeySource = (xprv.xkey.fingerprint(&secp), path.clone());
-                    let derived_xpub = xprv.xkey.derive_priv(&secp, &path)?;
-                    derived_xpub.into_descriptor_key(Some(key_source), descriptor_path)?
-                } else {
-                    xprv.xkey
-                        .into_descriptor_key(xprv.origin.clone(), descriptor_path)?
-                };
-                Ok(Arc::new(DescriptorKey {
-                    descriptor_key_mutex: Mutex::new(derived_descriptor_key),
-                }))
-            }
-            _ => Err(BdkError::Generic("Unsupported Key Type".to_string())),
-        }
-    }
+fn derive_key(key: &DescriptorKey, origin_path: &str) -> DescriptorKey {
+    key.derive(Some(&path(origin_path)), None).unwrap()
+}
 
-    fn as_public(&self) -> Arc<DescriptorKey> {
-        let secp = Secp256k1::new();
-        let root_key = self.descriptor_key_mutex.lock().unwrap();
-
-        match root_key.deref() {
-            BdkDescriptorKey::Public(descriptor_public_key, network, _) => {
-                Arc::new(DescriptorKey {
-                    descriptor_key_mutex: Mutex::new(BdkDescriptorKey::from_public(
-                        descriptor_public_key.clone(),
-                        network.clone(),
-                    )),
-                })
-            }
-            BdkDescriptorKey::Secret(descriptor_secret_key, network, _) => {
-                let descriptor_public_key = descriptor_secret_key.as_public(&secp).unwrap();
-                Arc::new(DescriptorKey {
-                    descriptor_key_mutex: Mutex::new(BdkDescriptorKey::from_public(
-                        descriptor_public_key,
-                        network.clone(),
-                    )),
-                })
-            }
-        }
-    }
+fn extend_key(key: &DescriptorKey, descriptor_path: &str) -> DescriptorKey {
+    key.derive(None, Some(&path(descriptor_path))).unwrap()
+}
 
-    fn as_string(&self) -> String {
-        let descriptor_key = self.descriptor_key_mutex.lock().unwrap();
-        match descriptor_key.deref() {
-            BdkDescriptorKey::Public(descriptor_public_key, _, _) => {
-                descriptor_public_key.to_string()
-            }
-            BdkDescriptorKey::Secret(descriptor_secret_key, _, _) => {
-                descriptor_secret_key.to_string()
-            }
-        }
-    }
+fn show_key(key: &str, message: &str) {
+    println!("{}", message);
+    println!("{}", key);
+    println!("================================")
 }
@@ -0,0 +1,135 @@
+use bdk::bitcoin::hashes::{sha256, Hash};
+use bdk::bitcoin::secp256k1::rand::{thread_rng, RngCore};
+use bdk::bitcoin::util::bip32::ExtendedPrivKey;
+use bdk::bitcoin::Network;
+use bdk::keys::bip39::{Language, Mnemonic as BdkMnemonic, WordCount};
+
+use crate::secp::secp;
+use crate::Error;
+
+/// A BIP39 mnemonic phrase in any of the BIP39 wordlist languages.
+///
+/// Parsing validates every word and the checksum; an unknown word is reported with its
+/// zero-based position in [`bip39::Error::UnknownWord`](bdk::keys::bip39::Error::UnknownWord).
+/// Words are NFKD normalized before lookup, and so is the passphrase when the seed is
+/// derived, so composed and decomposed input restore the same wallet.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mnemonic {
+    pub(crate) mnemonic: BdkMnemonic,
+}
+
+impl Mnemonic {
+    /// Generate a new English mnemonic with `word_count` words from the OS random number
+    /// generator.
+    pub fn new(word_count: WordCount) -> Self {
+        Self::new_in(Language::English, word_count)
+    }
+
+    /// Generate a new mnemonic in `language` with `word_count` words from the OS random
+    /// number generator.
+    pub fn new_in(language: Language, word_count: WordCount) -> Self {
+        let mut entropy = [0u8; 32];
+        let entropy_len = word_count as usize / 8;
+        thread_rng().fill_bytes(&mut entropy[..entropy_len]);
+        let mnemonic = BdkMnemonic::from_entropy_in(language, &entropy[..entropy_len])
+            .expect("entropy of a supported word count is always valid");
+        Self { mnemonic }
+    }
+
+    /// Parse and validate a mnemonic phrase, detecting its language from the words.
+    ///
+    /// Phrases whose words all appear in more than one wordlist (for example some Chinese
+    /// phrases) fail with [`bip39::Error::AmbiguousLanguages`](bdk::keys::bip39::Error::AmbiguousLanguages);
+    /// use [`Mnemonic::from_string_in`] for those.
+    pub fn from_string(mnemonic: String) -> Result<Self, Error> {
+        let mnemonic = BdkMnemonic::parse(mnemonic)?;
+        Ok(Self { mnemonic })
+    }
+
+    /// Parse and validate a mnemonic phrase in `language`.
+    pub fn from_string_in(language: Language, mnemonic: String) -> Result<Self, Error> {
+        let mnemonic = BdkMnemonic::parse_in(language, mnemonic)?;
+        Ok(Self { mnemonic })
+    }
+
+    /// Return the wordlist language of the phrase.
+    pub fn language(&self) -> Language {
+        self.mnemonic.language()
+    }
+
+    /// Encode raw entropy of 16, 20, 24, 28 or 32 bytes as an English mnemonic.
+    pub fn from_entropy(entropy: Vec<u8>) -> Result<Self, Error> {
+        Self::from_entropy_in(Language::English, entropy)
+    }
+
+    /// Encode raw entropy of 16, 20, 24, 28 or 32 bytes as a mnemonic in `language`.
+    pub fn from_entropy_in(language: Language, entropy: Vec<u8>) -> Result<Self, Error> {
+        let mnemonic = BdkMnemonic::from_entropy_in(language, &entropy)?;
+        Ok(Self { mnemonic })
+    }
+
+    /// Return the number of words in the phrase.
+    pub fn word_count(&self) -> usize {
+        self.mnemonic.word_count()
+    }
+
+    /// Return the entropy encoded by the phrase, without the checksum.
+    pub fn entropy(&self) -> Vec<u8> {
+        // `bip39::Mnemonic::to_entropy` detects the language again and panics on phrases that
+        // fit both Chinese wordlists, so decode the words with the language we parsed them in.
+        let mnemonic = &self.mnemonic;
+        let language = mnemonic.language();
+        let indices: Vec<u16> = mnemonic
+            .word_iter()
+            .map(|word| {
+                language
+                    .find_word(word)
+                    .expect("words of a parsed mnemonic are in its wordlist")
+            })
+            .collect();
+        decode_word_indices(&indices).0
+    }
+
+    /// Return the 64 byte BIP32 seed of the phrase with an optional `passphrase`.
+    pub fn seed(&self, passphrase: Option<String>) -> Vec<u8> {
+        self.mnemonic
+            .to_seed(passphrase.as_deref().unwrap_or(""))
+            .to_vec()
+    }
+
+    /// Return the fingerprint of the master key of the phrase with an optional `passphrase`,
+    /// as 8 hex characters.
+    pub fn master_fingerprint(&self, passphrase: Option<String>) -> String {
+        // The fingerprint does not depend on the network.
+        ExtendedPrivKey::new_master(Network::Bitcoin, &self.seed(passphrase))
+            .expect("a 64 byte seed is a valid master key")
+            .fingerprint(secp())
+            .to_string()
+    }
+
+    /// Return the phrase as space separated words.
+    pub fn as_string(&self) -> String {
+        self.mnemonic.to_string()
+    }
+}
+
+/// Decode wordlist `indices` into the entropy they encode and whether its checksum holds.
+pub(crate) fn decode_word_indices(indices: &[u16]) -> (Vec<u8>, bool) {
+    let bits: Vec<bool> = indices
+        .iter()
+        .flat_map(|index| (0..11).rev().map(move |bit| (index >> bit) & 1 == 1))
+        .collect();
+    let entropy_bytes = indices.len() / 3 * 4;
+    let entropy: Vec<u8> = bits
+        .chunks(8)
+        .take(entropy_bytes)
+        .map(|byte| byte.iter().fold(0u8, |acc, &bit| acc << 1 | bit as u8))
+        .collect();
+
+    let check = sha256::Hash::hash(&entropy);
+    let checksum_valid = bits[entropy_bytes * 8..]
+        .iter()
+        .enumerate()
+        .all(|(i, &bit)| bit == ((check[i / 8] >> (7 - i % 8)) & 1 == 1));
+    (entropy, checksum_valid)
+}
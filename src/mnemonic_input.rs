@@ -0,0 +1,166 @@
+use std::borrow::Cow;
+
+use bdk::keys::bip39::{Language, Mnemonic as BdkMnemonic};
+
+use crate::{Error, Mnemonic};
+
+/// Largest edit distance at which a wordlist word is still suggested for a typo.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// How a single token of user input matched the wordlist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordInput {
+    /// The token is a wordlist word.
+    Exact(String),
+    /// The token is a prefix of exactly one wordlist word.
+    Expanded {
+        /// The token as typed, after normalization.
+        input: String,
+        /// The word the prefix expands to.
+        word: String,
+    },
+    /// The token is neither a word nor a unique prefix and must be confirmed by the user.
+    Ambiguous {
+        /// The token as typed, after normalization.
+        input: String,
+        /// The words it is a prefix of, or else the nearest words by edit distance. Empty
+        /// when no word is close enough.
+        suggestions: Vec<String>,
+    },
+}
+
+impl WordInput {
+    /// Return the wordlist word this token resolved to, or `None` if it is ambiguous.
+    pub fn word(&self) -> Option<&str> {
+        match self {
+            WordInput::Exact(word) | WordInput::Expanded { word, .. } => Some(word),
+            WordInput::Ambiguous { .. } => None,
+        }
+    }
+}
+
+/// Mnemonic words as typed by a user, matched against a BIP39 wordlist.
+///
+/// Tokens are NFKD normalized and lowercased, then resolved as a whole word, as a unique
+/// prefix (every BIP39 word is unique in its first four letters), or left ambiguous with
+/// suggestions. Check [`MnemonicInput::ambiguous_positions`] before calling
+/// [`MnemonicInput::to_mnemonic`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MnemonicInput {
+    language: Language,
+    words: Vec<WordInput>,
+}
+
+impl MnemonicInput {
+    /// Match every whitespace separated token of `input` against the `language` wordlist.
+    pub fn new(language: Language, input: String) -> Self {
+        let mut input = Cow::Owned(input.to_lowercase());
+        BdkMnemonic::normalize_utf8_cow(&mut input);
+        let words = input
+            .split_whitespace()
+            .map(|token| match_word(language, token))
+            .collect();
+        Self { language, words }
+    }
+
+    /// Return the wordlist language the input was matched against.
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// Return how each token matched, in input order.
+    pub fn words(&self) -> Vec<WordInput> {
+        self.words.clone()
+    }
+
+    /// Return the zero-based positions of the tokens that did not resolve to a single word.
+    pub fn ambiguous_positions(&self) -> Vec<usize> {
+        self.words
+            .iter()
+            .enumerate()
+            .filter(|(_, word)| word.word().is_none())
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Build the mnemonic from the resolved words, validating the word count and checksum.
+    ///
+    /// Fails with [`Error::AmbiguousWords`] if any token is still ambiguous.
+    pub fn to_mnemonic(&self) -> Result<Mnemonic, Error> {
+        let ambiguous = self.ambiguous_positions();
+        if !ambiguous.is_empty() {
+            return Err(Error::AmbiguousWords(ambiguous));
+        }
+        let phrase = self
+            .words
+            .iter()
+            .filter_map(WordInput::word)
+            .collect::<Vec<_>>()
+            .join(" ");
+        Mnemonic::from_string_in(self.language, phrase)
+    }
+}
+
+fn match_word(language: Language, token: &str) -> WordInput {
+    // The Chinese wordlists are not sorted, so `Language::words_by_prefix` cannot be used
+    // to look up prefixes; the empty prefix returns the whole list.
+    let word_list = language.words_by_prefix("");
+
+    if word_list.contains(&token) {
+        return WordInput::Exact(token.to_string());
+    }
+
+    let prefixed: Vec<String> = word_list
+        .iter()
+        .filter(|word| word.starts_with(token))
+        .map(|word| word.to_string())
+        .collect();
+    if prefixed.len() == 1 {
+        return WordInput::Expanded {
+            input: token.to_string(),
+            word: prefixed[0].clone(),
+        };
+    }
+    if !prefixed.is_empty() {
+        return WordInput::Ambiguous {
+            input: token.to_string(),
+            suggestions: prefixed,
+        };
+    }
+
+    let distances: Vec<usize> = word_list
+        .iter()
+        .map(|word| edit_distance(token, word))
+        .collect();
+    let nearest = distances.iter().copied().min().unwrap_or(usize::MAX);
+    let suggestions = if nearest <= MAX_SUGGESTION_DISTANCE {
+        word_list
+            .iter()
+            .zip(distances)
+            .filter(|(_, distance)| *distance == nearest)
+            .map(|(word, _)| word.to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    WordInput::Ambiguous {
+        input: token.to_string(),
+        suggestions,
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != *b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
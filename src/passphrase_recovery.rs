@@ -0,0 +1,348 @@
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+
+use bdk::bitcoin::secp256k1::{Secp256k1, Signing};
+use bdk::bitcoin::util::bip32::{
+    DerivationPath as BdkDerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint,
+};
+use bdk::bitcoin::Network;
+use bdk::keys::bip39::Mnemonic as BdkMnemonic;
+
+use crate::recovery::parse_fingerprint;
+use crate::secp::secp;
+use crate::{DerivationPath, Error, Mnemonic};
+
+/// Candidates each worker thread checks between two progress reports.
+const CANDIDATES_PER_THREAD: u64 = 16;
+
+/// The largest number of candidates a pattern may have, about a trillion. At a few thousand
+/// candidates a second per core, searching that many already takes years.
+const MAX_CANDIDATES: u64 = 1 << 40;
+
+/// What a recovered passphrase must reproduce.
+#[derive(Debug, Clone)]
+pub enum PassphraseTarget {
+    /// The master key fingerprint, as 8 hex characters such as `d1d04177`.
+    Fingerprint(String),
+    /// An extended public key known to be at `path` from the master key. Only the key and
+    /// chain code are compared, so an `xpub` also matches a `tpub`.
+    Xpub {
+        /// The extended public key.
+        xpub: String,
+        /// The path of `xpub` from the master key.
+        path: DerivationPath,
+    },
+}
+
+/// A set of passphrase candidates built from slots of alternatives.
+///
+/// Each candidate concatenates one alternative from every slot, in slot order, so the
+/// candidates are the cartesian product of the slots. Candidates are numbered from `0`
+/// with the last slot varying fastest; the numbering is what a search resumes from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PassphrasePattern {
+    slots: Vec<Slot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Slot {
+    Words(Vec<String>),
+    /// Nothing or up to this many decimal digits, generated from the index on demand.
+    Digits(u32),
+}
+
+impl Slot {
+    /// Return the number of alternatives, or `None` if it does not fit in a `u64`.
+    fn len(&self) -> Option<u64> {
+        match self {
+            Slot::Words(words) => Some(words.len() as u64),
+            Slot::Digits(max_digits) => {
+                let (mut len, mut numbers) = (1u64, 1u64);
+                for _ in 0..*max_digits {
+                    numbers = numbers.checked_mul(10)?;
+                    len = len.checked_add(numbers)?;
+                }
+                Some(len)
+            }
+        }
+    }
+
+    /// Return alternative number `index`, which must be below [`Slot::len`].
+    fn alternative(&self, index: u64) -> String {
+        match self {
+            Slot::Words(words) => words[index as usize].clone(),
+            Slot::Digits(_) => {
+                // Nothing, then the 10 one digit numbers, then the 100 two digit numbers...
+                let (mut index, mut width, mut numbers) = (index, 0, 1u64);
+                while index >= numbers {
+                    index -= numbers;
+                    width += 1;
+                    numbers = numbers.saturating_mul(10);
+                }
+                if width == 0 {
+                    String::new()
+                } else {
+                    format!("{:0width$}", index, width = width)
+                }
+            }
+        }
+    }
+}
+
+impl PassphrasePattern {
+    /// Create a pattern whose only candidate is the empty passphrase.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a slot taking each of `words` as given.
+    pub fn with_words(mut self, words: Vec<String>) -> Self {
+        self.slots.push(Slot::Words(dedup(words)));
+        self
+    }
+
+    /// Add a slot taking each of `words` as given, lowercase, uppercase and capitalized.
+    pub fn with_casing_variants(mut self, words: Vec<String>) -> Self {
+        let variants = words
+            .iter()
+            .flat_map(|word| {
+                let mut chars = word.chars();
+                let capitalized = chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default();
+                [
+                    word.clone(),
+                    word.to_lowercase(),
+                    word.to_uppercase(),
+                    capitalized,
+                ]
+            })
+            .collect();
+        self.slots.push(Slot::Words(dedup(variants)));
+        self
+    }
+
+    /// Add a slot of nothing or up to `max_digits` decimal digits, shortest first.
+    pub fn with_digits(mut self, max_digits: u32) -> Self {
+        self.slots.push(Slot::Digits(max_digits));
+        self
+    }
+
+    /// Return the number of candidates.
+    ///
+    /// Fails with [`Error::InvalidPassphrasePattern`] if there are more than 2^40 (about a
+    /// trillion) candidates, too many to ever search.
+    pub fn candidate_count(&self) -> Result<u64, Error> {
+        self.slots
+            .iter()
+            .try_fold(1u64, |count, slot| count.checked_mul(slot.len()?))
+            .filter(|count| *count <= MAX_CANDIDATES)
+            .ok_or_else(|| {
+                Error::InvalidPassphrasePattern(format!("more than {} candidates", MAX_CANDIDATES))
+            })
+    }
+
+    /// Return candidate number `index`, or `None` past the last candidate or if the pattern
+    /// has too many candidates.
+    pub fn candidate(&self, mut index: u64) -> Option<String> {
+        if index >= self.candidate_count().ok()? {
+            return None;
+        }
+        let mut parts = Vec::with_capacity(self.slots.len());
+        for slot in self.slots.iter().rev() {
+            let len = slot.len()?;
+            parts.push(slot.alternative(index % len));
+            index /= len;
+        }
+        parts.reverse();
+        Some(parts.concat())
+    }
+}
+
+/// How far a [`PassphraseRecovery`] search has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassphraseProgress {
+    /// Every candidate below this index has been checked; resume from here.
+    pub checked: u64,
+    /// The number of candidates in the pattern.
+    pub total: u64,
+}
+
+/// The outcome of a [`PassphraseRecovery`] search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PassphraseSearch {
+    /// The passphrase was found at candidate number `index`.
+    Found {
+        /// The recovered passphrase.
+        passphrase: String,
+        /// Its index in the pattern.
+        index: u64,
+    },
+    /// No candidate matched.
+    Exhausted,
+    /// The progress callback stopped the search; no candidate below `resume_from` matched.
+    Stopped {
+        /// The index to resume the search from.
+        resume_from: u64,
+    },
+}
+
+#[derive(Debug)]
+enum Target {
+    Fingerprint(Fingerprint),
+    Xpub(ExtendedPubKey, BdkDerivationPath),
+}
+
+/// A search for the BIP39 passphrase of a mnemonic among the candidates of a pattern.
+///
+/// Every candidate is turned into a master key the same way [`DescriptorSecretKey::new`]
+/// does and compared with the target, on all cores.
+///
+/// [`DescriptorSecretKey::new`]: crate::DescriptorSecretKey::new
+#[derive(Debug)]
+pub struct PassphraseRecovery {
+    mnemonic: BdkMnemonic,
+    target: Target,
+    pattern: PassphrasePattern,
+    candidate_count: u64,
+}
+
+impl PassphraseRecovery {
+    /// Prepare a search for the passphrase of `mnemonic` among the `pattern` candidates.
+    ///
+    /// Fails with [`Error::InvalidPassphrasePattern`] if the pattern has too many candidates.
+    pub fn new(
+        mnemonic: &Mnemonic,
+        target: PassphraseTarget,
+        pattern: PassphrasePattern,
+    ) -> Result<Self, Error> {
+        let target = match target {
+            PassphraseTarget::Fingerprint(fingerprint) => {
+                Target::Fingerprint(parse_fingerprint(fingerprint)?)
+            }
+            PassphraseTarget::Xpub { xpub, path } => {
+                let xpub = ExtendedPubKey::from_str(&xpub)
+                    .map_err(|e| Error::InvalidKey(e.to_string()))?;
+                Target::Xpub(xpub, path.derivation_path)
+            }
+        };
+        Ok(Self {
+            mnemonic: mnemonic.mnemonic.clone(),
+            target,
+            candidate_count: pattern.candidate_count()?,
+            pattern,
+        })
+    }
+
+    /// Return the number of candidates in the pattern.
+    pub fn candidate_count(&self) -> u64 {
+        self.candidate_count
+    }
+
+    /// Check the candidates from `resume_from` on, in batches spread over one worker thread
+    /// per core.
+    ///
+    /// `progress` is called after each batch and stops the search by returning `false`.
+    /// Within a batch the lowest matching index wins, so the result does not depend on the
+    /// number of cores.
+    pub fn recover<F>(&self, resume_from: u64, mut progress: F) -> PassphraseSearch
+    where
+        F: FnMut(PassphraseProgress) -> bool,
+    {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get() as u64);
+        let batch = threads * CANDIDATES_PER_THREAD;
+        let total = self.candidate_count;
+
+        thread::scope(|scope| {
+            let (found_tx, found_rx) = mpsc::channel();
+            // Dropping the batch senders when the search ends stops the workers.
+            let workers: Vec<mpsc::Sender<Range<u64>>> = (0..threads)
+                .map(|worker| {
+                    let (batch_tx, batch_rx) = mpsc::channel::<Range<u64>>();
+                    let found_tx = found_tx.clone();
+                    scope.spawn(move || {
+                        for batch in batch_rx {
+                            let indexes =
+                                (batch.start + worker..batch.end).step_by(threads as usize);
+                            if found_tx.send(self.first_match(indexes)).is_err() {
+                                return;
+                            }
+                        }
+                    });
+                    batch_tx
+                })
+                .collect();
+
+            let mut start = resume_from;
+            while start < total {
+                let end = total.min(start + batch);
+                for worker in &workers {
+                    worker
+                        .send(start..end)
+                        .expect("workers run until the search ends");
+                }
+                let found = found_rx
+                    .iter()
+                    .take(workers.len())
+                    .flatten()
+                    .min_by_key(|(index, _)| *index);
+                if let Some((index, passphrase)) = found {
+                    return PassphraseSearch::Found { passphrase, index };
+                }
+                start = end;
+                if !progress(PassphraseProgress {
+                    checked: start,
+                    total,
+                }) && start < total
+                {
+                    return PassphraseSearch::Stopped { resume_from: start };
+                }
+            }
+            PassphraseSearch::Exhausted
+        })
+    }
+
+    fn first_match(&self, indexes: impl Iterator<Item = u64>) -> Option<(u64, String)> {
+        indexes
+            .map(|index| {
+                let passphrase = self
+                    .pattern
+                    .candidate(index)
+                    .expect("index is below the candidate count");
+                (index, passphrase)
+            })
+            .find(|(_, passphrase)| self.matches(secp(), passphrase))
+    }
+
+    fn matches<C: Signing>(&self, secp: &Secp256k1<C>, passphrase: &str) -> bool {
+        let seed = self.mnemonic.to_seed(passphrase);
+        // Neither the fingerprint nor the compared parts of the xpub depend on the network.
+        let master = match ExtendedPrivKey::new_master(Network::Bitcoin, &seed) {
+            Ok(master) => master,
+            Err(_) => return false,
+        };
+        match &self.target {
+            Target::Fingerprint(fingerprint) => master.fingerprint(secp) == *fingerprint,
+            Target::Xpub(xpub, path) => master
+                .derive_priv(secp, path)
+                .map(|xprv| {
+                    let derived = ExtendedPubKey::from_priv(secp, &xprv);
+                    derived.public_key == xpub.public_key && derived.chain_code == xpub.chain_code
+                })
+                .unwrap_or(false),
+        }
+    }
+}
+
+fn dedup(words: Vec<String>) -> Vec<String> {
+    let mut unique = Vec::with_capacity(words.len());
+    for word in words {
+        if !unique.contains(&word) {
+            unique.push(word);
+        }
+    }
+    unique
+}
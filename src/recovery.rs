@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+
+use bdk::bitcoin::secp256k1::Secp256k1;
+use bdk::bitcoin::util::bip32::{ExtendedPrivKey, Fingerprint};
+use bdk::bitcoin::Network;
+use bdk::keys::bip39::{Language, Mnemonic as BdkMnemonic};
+
+use crate::mnemonic::decode_word_indices;
+use crate::secp::secp;
+use crate::{Error, Mnemonic, MnemonicInput, WordInput};
+
+/// Token marking an illegible word in a recovery template.
+pub const MISSING_WORD: &str = "?";
+
+/// Most missing words a search will enumerate; each one multiplies the work by 2048.
+const MAX_MISSING_WORDS: usize = 2;
+
+const WORDS_IN_LIST: u32 = 2048;
+
+/// A search for the mnemonics a damaged backup could have been.
+///
+/// The template lists the known words, with [`MISSING_WORD`] in place of each illegible
+/// one. A template one word short of a valid length and without markers is treated as
+/// having lost a word at an unknown position, so every position is tried. Known words may
+/// be abbreviated as in [`MnemonicInput`].
+///
+/// Every candidate passing the BIP39 checksum is a match, unless a master fingerprint is
+/// given, in which case only candidates whose master key (with the given passphrase) has
+/// that fingerprint are kept.
+#[derive(Debug)]
+pub struct MnemonicRecovery {
+    language: Language,
+    templates: Vec<Vec<Option<u16>>>,
+    fingerprint: Option<Fingerprint>,
+    passphrase: String,
+}
+
+impl MnemonicRecovery {
+    /// Parse a recovery `template` in `language`, optionally filtering on a hex master
+    /// `fingerprint` such as `d1d04177`.
+    pub fn new(
+        language: Language,
+        template: String,
+        fingerprint: Option<String>,
+        passphrase: Option<String>,
+    ) -> Result<Self, Error> {
+        let fingerprint = fingerprint.map(parse_fingerprint).transpose()?;
+
+        let input = MnemonicInput::new(language, template);
+        let mut ambiguous = Vec::new();
+        let mut words = Vec::new();
+        for (position, word) in input.words().into_iter().enumerate() {
+            match word {
+                WordInput::Ambiguous { input, .. } if input == MISSING_WORD => words.push(None),
+                WordInput::Ambiguous { .. } => ambiguous.push(position),
+                word => {
+                    let word = word.word().expect("resolved words have a word");
+                    words.push(language.find_word(word));
+                }
+            }
+        }
+        if !ambiguous.is_empty() {
+            return Err(Error::AmbiguousWords(ambiguous));
+        }
+
+        let missing = words.iter().filter(|word| word.is_none()).count();
+        let templates = if is_valid_word_count(words.len()) {
+            vec![words]
+        } else if missing == 0 && is_valid_word_count(words.len() + 1) {
+            (0..=words.len())
+                .map(|position| {
+                    let mut template = words.clone();
+                    template.insert(position, None);
+                    template
+                })
+                .collect()
+        } else {
+            return Err(Error::InvalidRecoveryTemplate(format!(
+                "{} words is not a valid mnemonic length",
+                words.len()
+            )));
+        };
+
+        let missing = templates[0].iter().filter(|word| word.is_none()).count();
+        if missing == 0 {
+            return Err(Error::InvalidRecoveryTemplate(
+                "no word is marked as missing".to_string(),
+            ));
+        }
+        if missing > MAX_MISSING_WORDS {
+            return Err(Error::InvalidRecoveryTemplate(format!(
+                "{} missing words is more than the {} that can be searched",
+                missing, MAX_MISSING_WORDS
+            )));
+        }
+
+        Ok(Self {
+            language,
+            templates,
+            fingerprint,
+            passphrase: passphrase.unwrap_or_default(),
+        })
+    }
+
+    /// Return the number of phrases the search enumerates before checksum filtering.
+    pub fn candidate_count(&self) -> u64 {
+        self.templates
+            .iter()
+            .map(|template| {
+                let missing = template.iter().filter(|word| word.is_none()).count();
+                u64::from(WORDS_IN_LIST).pow(missing as u32)
+            })
+            .sum()
+    }
+
+    /// Run the search on every available core and return the matches in enumeration order,
+    /// without duplicates.
+    pub fn recover(&self) -> Vec<Mnemonic> {
+        let threads = thread::available_parallelism().map_or(1, usize::from);
+        self.recover_with_threads(threads)
+    }
+
+    /// Run the search on `threads` worker threads.
+    pub fn recover_with_threads(&self, threads: usize) -> Vec<Mnemonic> {
+        let threads = threads.max(1) as u64;
+        let total = self.candidate_count();
+        let found = Mutex::new(Vec::new());
+
+        thread::scope(|scope| {
+            for worker in 0..threads {
+                let found = &found;
+                scope.spawn(move || {
+                    let mut matches = Vec::new();
+                    let mut candidate = worker;
+                    while candidate < total {
+                        if let Some(entropy) = self.candidate_entropy(candidate) {
+                            if self.matches_fingerprint(secp(), &entropy) {
+                                matches.push((candidate, entropy));
+                            }
+                        }
+                        candidate += threads;
+                    }
+                    // The lock is only held to append, so it cannot be poisoned mid-update.
+                    found
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .extend(matches);
+                });
+            }
+        });
+
+        let mut found = found.into_inner().unwrap_or_else(PoisonError::into_inner);
+        found.sort();
+        let mut seen = HashSet::new();
+        found
+            .into_iter()
+            .filter_map(|(_, entropy)| {
+                if !seen.insert(entropy.clone()) {
+                    return None;
+                }
+                let mnemonic = BdkMnemonic::from_entropy_in(self.language, &entropy)
+                    .expect("entropy of a valid word count is always valid");
+                Some(Mnemonic { mnemonic })
+            })
+            .collect()
+    }
+
+    /// Fill in the missing words of candidate number `candidate` and return its entropy if
+    /// the checksum holds.
+    fn candidate_entropy(&self, mut candidate: u64) -> Option<Vec<u8>> {
+        let mut templates = self.templates.iter();
+        let template = loop {
+            let template = templates.next()?;
+            let missing = template.iter().filter(|word| word.is_none()).count();
+            let count = u64::from(WORDS_IN_LIST).pow(missing as u32);
+            if candidate < count {
+                break template;
+            }
+            candidate -= count;
+        };
+
+        let mut indices = Vec::with_capacity(template.len());
+        for word in template {
+            indices.push(word.unwrap_or_else(|| {
+                let index = (candidate % u64::from(WORDS_IN_LIST)) as u16;
+                candidate /= u64::from(WORDS_IN_LIST);
+                index
+            }));
+        }
+        let (entropy, checksum_valid) = decode_word_indices(&indices);
+        checksum_valid.then_some(entropy)
+    }
+
+    fn matches_fingerprint<C: bdk::bitcoin::secp256k1::Signing>(
+        &self,
+        secp: &Secp256k1<C>,
+        entropy: &[u8],
+    ) -> bool {
+        let fingerprint = match self.fingerprint {
+            Some(fingerprint) => fingerprint,
+            None => return true,
+        };
+        let mnemonic = BdkMnemonic::from_entropy_in(self.language, entropy)
+            .expect("entropy of a valid word count is always valid");
+        let seed = mnemonic.to_seed(self.passphrase.as_str());
+        // The fingerprint does not depend on the network.
+        ExtendedPrivKey::new_master(Network::Bitcoin, &seed)
+            .map(|master| master.fingerprint(secp) == fingerprint)
+            .unwrap_or(false)
+    }
+}
+
+/// Parse a master key fingerprint written as 8 hex characters.
+pub(crate) fn parse_fingerprint(fingerprint: String) -> Result<Fingerprint, Error> {
+    Fingerprint::from_str(&fingerprint).map_err(|_| Error::InvalidFingerprint(fingerprint))
+}
+
+fn is_valid_word_count(words: usize) -> bool {
+    (12..=24).contains(&words) && words.is_multiple_of(3)
+}
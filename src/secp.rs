@@ -0,0 +1,18 @@
+use std::sync::OnceLock;
+
+use bdk::bitcoin::secp256k1::rand::thread_rng;
+use bdk::bitcoin::secp256k1::{All, Secp256k1};
+
+/// Return the secp256k1 context shared by every key operation.
+///
+/// Creating a context allocates and precomputes tables, which used to dominate bulk
+/// derivation when every call made its own. The shared context is created on first use,
+/// randomized against side channels and, being immutable, safe to use from any thread.
+pub(crate) fn secp() -> &'static Secp256k1<All> {
+    static SECP: OnceLock<Secp256k1<All>> = OnceLock::new();
+    SECP.get_or_init(|| {
+        let mut secp = Secp256k1::new();
+        secp.randomize(&mut thread_rng());
+        secp
+    })
+}
@@ -0,0 +1,204 @@
+use bdk::keys::bip39::{Language, Mnemonic as BdkMnemonic};
+use qrcode::bits::Bits;
+use qrcode::render::{svg, unicode};
+use qrcode::{EcLevel, QrCode, Version};
+
+use crate::{Error, Mnemonic};
+
+/// Light modules around the code, as the QR specification requires.
+const QUIET_ZONE: usize = 4;
+
+/// The two SeedQR encodings of an English BIP39 mnemonic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedQrFormat {
+    /// The four digit wordlist index of every word, in numeric mode: 25x25 modules for 12
+    /// words and 29x29 for 24.
+    Standard,
+    /// The entropy without its checksum, in byte mode: 21x21 modules for 12 words and 25x25
+    /// for 24.
+    Compact,
+}
+
+/// A SeedQR, the QR code SeedSigner and compatible signing devices scan to load a mnemonic.
+///
+/// Only 12 and 24 word English mnemonics have a SeedQR. Everything is encoded and rendered
+/// locally; nothing leaves the process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedQr {
+    format: SeedQrFormat,
+    payload: Vec<u8>,
+}
+
+impl SeedQr {
+    /// Encode `mnemonic` as a SeedQR in `format`.
+    pub fn new(mnemonic: &Mnemonic, format: SeedQrFormat) -> Result<Self, Error> {
+        let mnemonic = &mnemonic.mnemonic;
+        if mnemonic.language() != Language::English {
+            return Err(Error::InvalidSeedQr(
+                "only English mnemonics have a SeedQR".to_string(),
+            ));
+        }
+        check_word_count(mnemonic.word_count())?;
+        let payload = match format {
+            SeedQrFormat::Standard => mnemonic
+                .word_iter()
+                .map(|word| {
+                    let index = Language::English
+                        .find_word(word)
+                        .expect("words of a parsed mnemonic are in its wordlist");
+                    format!("{:04}", index)
+                })
+                .collect::<String>()
+                .into_bytes(),
+            SeedQrFormat::Compact => mnemonic.to_entropy(),
+        };
+        Ok(Self { format, payload })
+    }
+
+    /// Decode the data of a scanned SeedQR: 48 or 96 ASCII digits for a Standard SeedQR, or
+    /// 16 or 32 bytes of entropy for a Compact one.
+    pub fn from_payload(payload: Vec<u8>) -> Result<Self, Error> {
+        let format = match payload.len() {
+            16 | 32 => SeedQrFormat::Compact,
+            48 | 96 => SeedQrFormat::Standard,
+            len => {
+                return Err(Error::InvalidSeedQr(format!(
+                    "{} bytes is not the length of a SeedQR",
+                    len
+                )))
+            }
+        };
+        let seed_qr = Self { format, payload };
+        seed_qr.decode()?;
+        Ok(seed_qr)
+    }
+
+    /// Return the encoding of the SeedQR.
+    pub fn format(&self) -> SeedQrFormat {
+        self.format
+    }
+
+    /// Return the data the QR code holds: ASCII digits for a Standard SeedQR and raw
+    /// entropy for a Compact one.
+    pub fn payload(&self) -> Vec<u8> {
+        self.payload.clone()
+    }
+
+    /// Return the mnemonic the SeedQR encodes.
+    pub fn mnemonic(&self) -> Mnemonic {
+        Mnemonic {
+            mnemonic: self.decode().expect("the payload was validated"),
+        }
+    }
+
+    /// Render the QR code as lines of Unicode half blocks, two modules per character, for
+    /// printing to a terminal with a dark foreground on a light background.
+    pub fn to_terminal(&self) -> String {
+        self.qr_code().render::<unicode::Dense1x2>().build()
+    }
+
+    /// Render the QR code as an SVG document with `module_size` pixels per module.
+    pub fn to_svg(&self, module_size: u32) -> String {
+        self.qr_code()
+            .render::<svg::Color>()
+            .module_dimensions(module_size, module_size)
+            .build()
+    }
+
+    /// Render the QR code as a greyscale PNG image with `module_size` pixels per module.
+    pub fn to_png(&self, module_size: u32) -> Vec<u8> {
+        let code = self.qr_code();
+        let modules = code.width() + 2 * QUIET_ZONE;
+        let module_size = module_size.max(1) as usize;
+        let size = modules * module_size;
+        let colors = code.to_colors();
+        let pixels: Vec<u8> = (0..size * size)
+            .map(|pixel| {
+                let x = (pixel % size) / module_size;
+                let y = (pixel / size) / module_size;
+                let dark = (QUIET_ZONE..QUIET_ZONE + code.width()).contains(&x)
+                    && (QUIET_ZONE..QUIET_ZONE + code.width()).contains(&y)
+                    && colors[(y - QUIET_ZONE) * code.width() + x - QUIET_ZONE]
+                        == qrcode::Color::Dark;
+                if dark {
+                    0x00
+                } else {
+                    0xff
+                }
+            })
+            .collect();
+
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, size as u32, size as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .expect("writing a PNG to memory does not fail");
+        png
+    }
+
+    /// Decode the payload into the mnemonic it encodes.
+    fn decode(&self) -> Result<BdkMnemonic, Error> {
+        Ok(match self.format {
+            SeedQrFormat::Standard => {
+                let indices = self
+                    .payload
+                    .chunks(4)
+                    .map(|digits| {
+                        std::str::from_utf8(digits)
+                            .ok()
+                            .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+                            .and_then(|digits| digits.parse::<usize>().ok())
+                            .filter(|index| *index < 2048)
+                            .ok_or_else(|| {
+                                Error::InvalidSeedQr(format!(
+                                    "{:?} is not a wordlist index",
+                                    String::from_utf8_lossy(digits)
+                                ))
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let words: Vec<&str> = indices
+                    .into_iter()
+                    .map(|index| Language::English.word_list()[index])
+                    .collect();
+                BdkMnemonic::parse_in(Language::English, words.join(" "))?
+            }
+            SeedQrFormat::Compact => {
+                BdkMnemonic::from_entropy_in(Language::English, &self.payload)?
+            }
+        })
+    }
+
+    /// Build the QR code at the version the SeedQR specification fixes, in numeric mode for
+    /// Standard and byte mode for Compact, so every device draws the same modules.
+    fn qr_code(&self) -> QrCode {
+        let twelve_words = matches!(self.payload.len(), 16 | 48);
+        let version = match (self.format, twelve_words) {
+            (SeedQrFormat::Standard, true) => 2,
+            (SeedQrFormat::Standard, false) => 3,
+            (SeedQrFormat::Compact, true) => 1,
+            (SeedQrFormat::Compact, false) => 2,
+        };
+        let mut bits = Bits::new(Version::Normal(version));
+        match self.format {
+            SeedQrFormat::Standard => bits.push_numeric_data(&self.payload),
+            SeedQrFormat::Compact => bits.push_byte_data(&self.payload),
+        }
+        .and_then(|_| bits.push_terminator(EcLevel::L))
+        .and_then(|_| QrCode::with_bits(bits, EcLevel::L))
+        .expect("a SeedQR payload fits its QR version")
+    }
+}
+
+fn check_word_count(word_count: usize) -> Result<(), Error> {
+    match word_count {
+        12 | 24 => Ok(()),
+        _ => Err(Error::InvalidSeedQr(format!(
+            "a SeedQR holds 12 or 24 words, not {}",
+            word_count
+        ))),
+    }
+}
@@ -0,0 +1,87 @@
+use bdk::bitcoin::secp256k1::rand::{thread_rng, RngCore};
+use bdk::keys::bip39::Mnemonic as BdkMnemonic;
+
+use crate::{Error, Mnemonic};
+
+/// Seed XOR, compatible with Coldcard.
+///
+/// Every share is itself a valid BIP39 phrase with the word count of the secret, and the
+/// secret's entropy is the XOR of the shares' entropy, with its checksum recomputed. All
+/// shares are needed to recombine, in any order.
+impl Mnemonic {
+    /// Split the phrase into `shares` Seed XOR shares of the same word count and language.
+    ///
+    /// All shares but the last are drawn from the OS random number generator.
+    pub fn seed_xor_split(&self, shares: usize) -> Result<Vec<Mnemonic>, Error> {
+        if shares < 2 {
+            return Err(Error::InvalidShares(
+                "Seed XOR needs at least 2 shares".to_string(),
+            ));
+        }
+        let language = self.language();
+        let mut last = self.entropy();
+        let mut entropies = Vec::with_capacity(shares);
+        for _ in 1..shares {
+            let mut entropy = vec![0u8; last.len()];
+            thread_rng().fill_bytes(&mut entropy);
+            xor_into(&mut last, &entropy);
+            entropies.push(entropy);
+        }
+        entropies.push(last);
+
+        entropies
+            .iter()
+            .map(|entropy| {
+                let mnemonic = BdkMnemonic::from_entropy_in(language, entropy)?;
+                Ok(Mnemonic { mnemonic })
+            })
+            .collect()
+    }
+
+    /// Combine Seed XOR `shares` into the secret phrase, in the language of the first share.
+    ///
+    /// When `expected_fingerprint` is given, the master fingerprint of the result (without
+    /// passphrase, as Coldcard displays it) must match it, which catches a missing or
+    /// mistyped share.
+    pub fn seed_xor_combine(
+        shares: Vec<Mnemonic>,
+        expected_fingerprint: Option<String>,
+    ) -> Result<Self, Error> {
+        let (first, rest) = match shares.split_first() {
+            Some((first, rest)) if !rest.is_empty() => (first, rest),
+            _ => {
+                return Err(Error::InvalidShares(
+                    "Seed XOR needs at least 2 shares".to_string(),
+                ))
+            }
+        };
+        let mut entropy = first.entropy();
+        for share in rest {
+            let share = share.entropy();
+            if share.len() != entropy.len() {
+                return Err(Error::InvalidShares(
+                    "all shares must have the same number of words".to_string(),
+                ));
+            }
+            xor_into(&mut entropy, &share);
+        }
+
+        let mnemonic = Mnemonic {
+            mnemonic: BdkMnemonic::from_entropy_in(first.language(), &entropy)?,
+        };
+        if let Some(expected) = expected_fingerprint {
+            let found = mnemonic.master_fingerprint(None);
+            if !found.eq_ignore_ascii_case(&expected) {
+                return Err(Error::FingerprintMismatch { expected, found });
+            }
+        }
+        Ok(mnemonic)
+    }
+}
+
+fn xor_into(target: &mut [u8], other: &[u8]) {
+    target
+        .iter_mut()
+        .zip(other)
+        .for_each(|(byte, other)| *byte ^= other);
+}
@@ -0,0 +1,607 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use bdk::bitcoin::hashes::hmac::{Hmac, HmacEngine};
+use bdk::bitcoin::hashes::{sha256, Hash, HashEngine};
+use bdk::bitcoin::secp256k1::rand::{thread_rng, RngCore};
+
+use crate::Error;
+
+const WORDLIST: &str = include_str!("slip39_wordlist.txt");
+const RADIX_BITS: usize = 10;
+const CHECKSUM_WORDS: usize = 3;
+/// Identifier, flags, iteration exponent and share parameters, followed by the checksum.
+const METADATA_WORDS: usize = 4 + CHECKSUM_WORDS;
+const MIN_SECRET_BYTES: usize = 16;
+const MAX_SHARE_COUNT: usize = 16;
+const BASE_ITERATION_COUNT: u32 = 10000;
+const ROUND_COUNT: u8 = 4;
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const DIGEST_BYTES: usize = 4;
+
+/// A SLIP-39 group: any `member_threshold` of its `member_count` shares recover the group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slip39Group {
+    /// The number of member shares needed to recover the group.
+    pub member_threshold: u8,
+    /// The number of member shares the group is split into.
+    pub member_count: u8,
+}
+
+/// SLIP-39 Shamir backup of a master secret, as used by Trezor.
+///
+/// The master secret is encrypted with an optional passphrase and split into groups, each of
+/// which is split into member shares. Any `group_threshold` groups recover the secret, and a
+/// group is recovered from `member_threshold` of its shares. The secret is used as the BIP32
+/// seed, see [`DescriptorSecretKey::from_slip39`](crate::DescriptorSecretKey::from_slip39).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slip39 {
+    group_threshold: u8,
+    groups: Vec<Slip39Group>,
+    iteration_exponent: u8,
+    extendable: bool,
+}
+
+impl Slip39 {
+    /// Describe a split into `groups`, any `group_threshold` of which recover the secret.
+    ///
+    /// Shares are extendable and use iteration exponent 1 unless changed.
+    pub fn new(group_threshold: u8, groups: Vec<Slip39Group>) -> Result<Self, Error> {
+        if groups.is_empty() || groups.len() > MAX_SHARE_COUNT {
+            return Err(invalid(format!(
+                "there must be 1 to {} groups",
+                MAX_SHARE_COUNT
+            )));
+        }
+        if group_threshold == 0 || usize::from(group_threshold) > groups.len() {
+            return Err(invalid(format!(
+                "the group threshold must be 1 to {}",
+                groups.len()
+            )));
+        }
+        for group in &groups {
+            if group.member_threshold == 0
+                || group.member_threshold > group.member_count
+                || usize::from(group.member_count) > MAX_SHARE_COUNT
+            {
+                return Err(invalid(format!(
+                    "{} of {} is not a valid member threshold",
+                    group.member_threshold, group.member_count
+                )));
+            }
+            if group.member_threshold == 1 && group.member_count > 1 {
+                return Err(invalid(
+                    "use 1 of 1 instead of several shares with member threshold 1".to_string(),
+                ));
+            }
+        }
+        Ok(Self {
+            group_threshold,
+            groups,
+            iteration_exponent: 1,
+            extendable: true,
+        })
+    }
+
+    /// Use `10000 * 2^iteration_exponent` PBKDF2 iterations to encrypt the secret, from 0 to 15.
+    pub fn with_iteration_exponent(mut self, iteration_exponent: u8) -> Self {
+        self.iteration_exponent = iteration_exponent;
+        self
+    }
+
+    /// Set whether the shares are extendable, which lets more shares be made later for the
+    /// same secret and passphrase. Non-extendable shares bind the encryption to the share
+    /// identifier, as in the first revision of SLIP-39.
+    pub fn with_extendable(mut self, extendable: bool) -> Self {
+        self.extendable = extendable;
+        self
+    }
+
+    /// Encrypt `master_secret`, of at least 16 bytes and an even length, with `passphrase`
+    /// and split it into mnemonics, one list per group.
+    ///
+    /// The passphrase must be printable ASCII. The share identifier and the random parts of
+    /// the polynomials are drawn from the OS random number generator.
+    pub fn split(
+        &self,
+        master_secret: Vec<u8>,
+        passphrase: Option<String>,
+    ) -> Result<Vec<Vec<String>>, Error> {
+        if master_secret.len() < MIN_SECRET_BYTES || master_secret.len() % 2 == 1 {
+            return Err(invalid(format!(
+                "the master secret must be an even number of at least {} bytes, not {}",
+                MIN_SECRET_BYTES,
+                master_secret.len()
+            )));
+        }
+        if self.iteration_exponent > 15 {
+            return Err(invalid(format!(
+                "iteration exponent {} is above 15",
+                self.iteration_exponent
+            )));
+        }
+        let passphrase = check_passphrase(passphrase)?;
+        let identifier = (thread_rng().next_u32() & 0x7fff) as u16;
+        let encrypted = crypt(
+            &master_secret,
+            &passphrase,
+            self.iteration_exponent,
+            identifier,
+            self.extendable,
+            false,
+        );
+
+        let group_secrets = split_secret(self.group_threshold, self.groups.len() as u8, &encrypted);
+        Ok(self
+            .groups
+            .iter()
+            .zip(group_secrets)
+            .map(|(group, (group_index, group_secret))| {
+                split_secret(group.member_threshold, group.member_count, &group_secret)
+                    .into_iter()
+                    .map(|(member_index, value)| {
+                        Share {
+                            identifier,
+                            extendable: self.extendable,
+                            iteration_exponent: self.iteration_exponent,
+                            group_index,
+                            group_threshold: self.group_threshold,
+                            group_count: self.groups.len() as u8,
+                            member_index,
+                            member_threshold: group.member_threshold,
+                            value,
+                        }
+                        .to_mnemonic()
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Recover the master secret from `shares` and the `passphrase` they were made with.
+    ///
+    /// Shares may be given in any order. Groups with fewer shares than their member threshold
+    /// are ignored as long as enough other groups are complete. A wrong passphrase cannot be
+    /// detected and yields a different secret.
+    pub fn combine(shares: Vec<String>, passphrase: Option<String>) -> Result<Vec<u8>, Error> {
+        let passphrase = check_passphrase(passphrase)?;
+        let shares = shares
+            .iter()
+            .map(|share| Share::from_mnemonic(share))
+            .collect::<Result<Vec<_>, _>>()?;
+        let first = shares
+            .first()
+            .ok_or_else(|| invalid("no shares were given".to_string()))?;
+
+        let mut groups: BTreeMap<u8, Vec<&Share>> = BTreeMap::new();
+        for share in &shares {
+            if share.identifier != first.identifier
+                || share.extendable != first.extendable
+                || share.iteration_exponent != first.iteration_exponent
+            {
+                return Err(invalid(
+                    "all shares must begin with the same two words".to_string(),
+                ));
+            }
+            if share.group_threshold != first.group_threshold
+                || share.group_count != first.group_count
+            {
+                return Err(invalid(
+                    "all shares must have the same group threshold and group count".to_string(),
+                ));
+            }
+            if share.value.len() != first.value.len() {
+                return Err(invalid(
+                    "all shares must have the same number of words".to_string(),
+                ));
+            }
+            let group = groups.entry(share.group_index).or_default();
+            if let Some(other) = group.first() {
+                if other.member_threshold != share.member_threshold {
+                    return Err(invalid(format!(
+                        "the shares of group {} have different member thresholds",
+                        share.group_index + 1
+                    )));
+                }
+            }
+            match group
+                .iter()
+                .find(|other| other.member_index == share.member_index)
+            {
+                Some(other) if other.value == share.value => {}
+                Some(_) => {
+                    return Err(invalid(format!(
+                        "group {} has two different shares with member index {}",
+                        share.group_index + 1,
+                        share.member_index + 1
+                    )))
+                }
+                None => group.push(share),
+            }
+        }
+
+        let group_secrets = groups
+            .iter()
+            .filter(|(_, members)| members.len() >= usize::from(members[0].member_threshold))
+            .take(usize::from(first.group_threshold))
+            .map(|(group_index, members)| {
+                let threshold = members[0].member_threshold;
+                let points: Vec<_> = members
+                    .iter()
+                    .take(usize::from(threshold))
+                    .map(|share| (share.member_index, share.value.clone()))
+                    .collect();
+                Ok((*group_index, recover_secret(threshold, &points)?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        if group_secrets.len() < usize::from(first.group_threshold) {
+            return Err(invalid(format!(
+                "{} complete groups are needed but {} were given",
+                first.group_threshold,
+                group_secrets.len()
+            )));
+        }
+
+        let encrypted = recover_secret(first.group_threshold, &group_secrets)?;
+        Ok(crypt(
+            &encrypted,
+            &passphrase,
+            first.iteration_exponent,
+            first.identifier,
+            first.extendable,
+            true,
+        ))
+    }
+}
+
+/// A single decoded share.
+struct Share {
+    identifier: u16,
+    extendable: bool,
+    iteration_exponent: u8,
+    group_index: u8,
+    group_threshold: u8,
+    group_count: u8,
+    member_index: u8,
+    member_threshold: u8,
+    value: Vec<u8>,
+}
+
+impl Share {
+    fn from_mnemonic(mnemonic: &str) -> Result<Self, Error> {
+        let indices = mnemonic
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                wordlist()
+                    .binary_search(&word.as_str())
+                    .map(|index| index as u16)
+                    .map_err(|_| invalid(format!("{:?} is not a SLIP-39 word", word)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let min_words = METADATA_WORDS + (MIN_SECRET_BYTES * 8).div_ceil(RADIX_BITS);
+        if indices.len() < min_words {
+            return Err(invalid(format!(
+                "a share has at least {} words but {} were given",
+                min_words,
+                indices.len()
+            )));
+        }
+        let padding = RADIX_BITS * (indices.len() - METADATA_WORDS) % 16;
+        if padding > 8 {
+            return Err(invalid(format!(
+                "{} words is not a valid share length",
+                indices.len()
+            )));
+        }
+
+        let header = bits(&indices[..4]);
+        let extendable = header[15];
+        if !rs1024_verify(&indices, extendable) {
+            return Err(invalid("invalid share checksum".to_string()));
+        }
+        let field = |start: usize, len: usize| {
+            header[start..start + len]
+                .iter()
+                .fold(0u16, |acc, &bit| (acc << 1) | u16::from(bit))
+        };
+        let share = Self {
+            identifier: field(0, 15),
+            extendable,
+            iteration_exponent: field(16, 4) as u8,
+            group_index: field(20, 4) as u8,
+            group_threshold: field(24, 4) as u8 + 1,
+            group_count: field(28, 4) as u8 + 1,
+            member_index: field(32, 4) as u8,
+            member_threshold: field(36, 4) as u8 + 1,
+            value: {
+                let value = bits(&indices[4..indices.len() - CHECKSUM_WORDS]);
+                if value[..padding].iter().any(|&bit| bit) {
+                    return Err(invalid("invalid share padding".to_string()));
+                }
+                value[padding..]
+                    .chunks(8)
+                    .map(|byte| {
+                        byte.iter()
+                            .fold(0u8, |acc, &bit| (acc << 1) | u8::from(bit))
+                    })
+                    .collect()
+            },
+        };
+        if share.group_threshold > share.group_count {
+            return Err(invalid(
+                "the group threshold is above the group count".to_string(),
+            ));
+        }
+        Ok(share)
+    }
+
+    fn to_mnemonic(&self) -> String {
+        let mut data = Vec::new();
+        push_bits(&mut data, u32::from(self.identifier), 15);
+        push_bits(&mut data, u32::from(self.extendable), 1);
+        for (value, len) in [
+            (self.iteration_exponent, 4),
+            (self.group_index, 4),
+            (self.group_threshold - 1, 4),
+            (self.group_count - 1, 4),
+            (self.member_index, 4),
+            (self.member_threshold - 1, 4),
+        ] {
+            push_bits(&mut data, u32::from(value), len);
+        }
+        let padding =
+            (self.value.len() * 8).div_ceil(RADIX_BITS) * RADIX_BITS - self.value.len() * 8;
+        push_bits(&mut data, 0, padding);
+        for byte in &self.value {
+            push_bits(&mut data, u32::from(*byte), 8);
+        }
+
+        let mut indices: Vec<u16> = data
+            .chunks(RADIX_BITS)
+            .map(|word| {
+                word.iter()
+                    .fold(0u16, |acc, &bit| (acc << 1) | u16::from(bit))
+            })
+            .collect();
+        indices.extend(rs1024_checksum(&indices, self.extendable));
+        indices
+            .iter()
+            .map(|&index| wordlist()[usize::from(index)])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::InvalidShares(message)
+}
+
+fn wordlist() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| WORDLIST.lines().collect())
+}
+
+fn bits(indices: &[u16]) -> Vec<bool> {
+    indices
+        .iter()
+        .flat_map(|index| {
+            (0..RADIX_BITS)
+                .rev()
+                .map(move |bit| (index >> bit) & 1 == 1)
+        })
+        .collect()
+}
+
+fn push_bits(data: &mut Vec<bool>, value: u32, len: usize) {
+    data.extend((0..len).rev().map(|bit| (value >> bit) & 1 == 1));
+}
+
+fn check_passphrase(passphrase: Option<String>) -> Result<Vec<u8>, Error> {
+    let passphrase = passphrase.unwrap_or_default();
+    if !passphrase.bytes().all(|byte| (32..=126).contains(&byte)) {
+        return Err(invalid(
+            "the passphrase must be printable ASCII".to_string(),
+        ));
+    }
+    Ok(passphrase.into_bytes())
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+    if extendable {
+        b"shamir_extendable"
+    } else {
+        b"shamir"
+    }
+}
+
+fn rs1024_polymod(values: impl Iterator<Item = u16>) -> u32 {
+    const GENERATOR: [u32; 10] = [
+        0xe0e040, 0x1c1c080, 0x3838100, 0x7070200, 0xe0e0009, 0x1c0c2412, 0x38086c24, 0x3090fc48,
+        0x21b1f890, 0x3f3f120,
+    ];
+    values.fold(1, |checksum, value| {
+        let top = checksum >> 20;
+        let checksum = ((checksum & 0xfffff) << 10) ^ u32::from(value);
+        (0..10)
+            .filter(|bit| (top >> bit) & 1 == 1)
+            .fold(checksum, |checksum, bit| checksum ^ GENERATOR[bit])
+    })
+}
+
+fn rs1024_checksum(data: &[u16], extendable: bool) -> Vec<u16> {
+    let values = customization(extendable)
+        .iter()
+        .map(|&byte| u16::from(byte))
+        .chain(data.iter().copied())
+        .chain([0; CHECKSUM_WORDS]);
+    let polymod = rs1024_polymod(values) ^ 1;
+    (0..CHECKSUM_WORDS)
+        .rev()
+        .map(|word| ((polymod >> (RADIX_BITS * word)) & 1023) as u16)
+        .collect()
+}
+
+fn rs1024_verify(data: &[u16], extendable: bool) -> bool {
+    let values = customization(extendable)
+        .iter()
+        .map(|&byte| u16::from(byte))
+        .chain(data.iter().copied());
+    rs1024_polymod(values) == 1
+}
+
+/// Exponent and logarithm tables of GF(256) with the Rijndael polynomial and generator 3.
+fn gf256_tables() -> &'static ([u8; 255], [u8; 256]) {
+    static TABLES: OnceLock<([u8; 255], [u8; 256])> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut exp = [0u8; 255];
+        let mut log = [0u8; 256];
+        let mut poly: u16 = 1;
+        for (i, entry) in exp.iter_mut().enumerate() {
+            *entry = poly as u8;
+            log[usize::from(poly)] = i as u8;
+            poly ^= poly << 1;
+            if poly & 0x100 != 0 {
+                poly ^= 0x11b;
+            }
+        }
+        (exp, log)
+    })
+}
+
+/// Evaluate at `x` the polynomial through `points`, which have distinct x coordinates.
+fn interpolate(points: &[(u8, Vec<u8>)], x: u8) -> Vec<u8> {
+    if let Some((_, value)) = points.iter().find(|(point_x, _)| *point_x == x) {
+        return value.clone();
+    }
+    let (exp, log) = gf256_tables();
+    let log = |value: u8| usize::from(log[usize::from(value)]);
+    let log_product: usize = points.iter().map(|(point_x, _)| log(point_x ^ x)).sum();
+
+    let mut result = vec![0u8; points[0].1.len()];
+    for (point_x, value) in points {
+        let log_denominator: usize = points
+            .iter()
+            .filter(|(other_x, _)| other_x != point_x)
+            .map(|(other_x, _)| log(point_x ^ other_x))
+            .sum();
+        let log_basis =
+            (log_product - log(point_x ^ x) + 255 * points.len() - log_denominator) % 255;
+        for (result, byte) in result.iter_mut().zip(value) {
+            if *byte != 0 {
+                *result ^= exp[(log(*byte) + log_basis) % 255];
+            }
+        }
+    }
+    result
+}
+
+fn secret_digest(random_part: &[u8], secret: &[u8]) -> Vec<u8> {
+    let mut engine = HmacEngine::<sha256::Hash>::new(random_part);
+    engine.input(secret);
+    Hmac::from_engine(engine)[..DIGEST_BYTES].to_vec()
+}
+
+fn split_secret(threshold: u8, share_count: u8, secret: &[u8]) -> Vec<(u8, Vec<u8>)> {
+    if threshold == 1 {
+        return (0..share_count)
+            .map(|index| (index, secret.to_vec()))
+            .collect();
+    }
+    let random = |len: usize| {
+        let mut bytes = vec![0u8; len];
+        thread_rng().fill_bytes(&mut bytes);
+        bytes
+    };
+    let mut shares: Vec<_> = (0..threshold - 2)
+        .map(|index| (index, random(secret.len())))
+        .collect();
+    let random_part = random(secret.len() - DIGEST_BYTES);
+    let mut digest = secret_digest(&random_part, secret);
+    digest.extend(random_part);
+
+    let mut points = shares.clone();
+    points.push((DIGEST_INDEX, digest));
+    points.push((SECRET_INDEX, secret.to_vec()));
+    for index in threshold - 2..share_count {
+        shares.push((index, interpolate(&points, index)));
+    }
+    shares
+}
+
+fn recover_secret(threshold: u8, points: &[(u8, Vec<u8>)]) -> Result<Vec<u8>, Error> {
+    if threshold == 1 {
+        return Ok(points[0].1.clone());
+    }
+    let secret = interpolate(points, SECRET_INDEX);
+    let digest = interpolate(points, DIGEST_INDEX);
+    if digest[..DIGEST_BYTES] != secret_digest(&digest[DIGEST_BYTES..], &secret)[..] {
+        return Err(invalid(
+            "the shares do not recover a valid secret".to_string(),
+        ));
+    }
+    Ok(secret)
+}
+
+/// Run the four round Feistel cipher over `data`, backwards when `decrypt` is set.
+fn crypt(
+    data: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+    decrypt: bool,
+) -> Vec<u8> {
+    let mut salt = Vec::new();
+    if !extendable {
+        salt.extend_from_slice(b"shamir");
+        salt.extend_from_slice(&identifier.to_be_bytes());
+    }
+    let iterations = (BASE_ITERATION_COUNT << iteration_exponent) / u32::from(ROUND_COUNT);
+    let (left, right) = data.split_at(data.len() / 2);
+    let (mut left, mut right) = (left.to_vec(), right.to_vec());
+    let rounds: Vec<u8> = if decrypt {
+        (0..ROUND_COUNT).rev().collect()
+    } else {
+        (0..ROUND_COUNT).collect()
+    };
+    for round in rounds {
+        let mut password = vec![round];
+        password.extend_from_slice(passphrase);
+        let mut round_salt = salt.clone();
+        round_salt.extend_from_slice(&right);
+        let output = pbkdf2_sha256(&password, &round_salt, iterations, right.len());
+        left.iter_mut()
+            .zip(output)
+            .for_each(|(byte, output)| *byte ^= output);
+        std::mem::swap(&mut left, &mut right);
+    }
+    right.extend(left);
+    right
+}
+
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32, len: usize) -> Vec<u8> {
+    let keyed = HmacEngine::<sha256::Hash>::new(password);
+    let mut output = Vec::with_capacity(len);
+    for block in 1u32.. {
+        if output.len() >= len {
+            break;
+        }
+        let mut engine = keyed.clone();
+        engine.input(salt);
+        engine.input(&block.to_be_bytes());
+        let mut u = Hmac::from_engine(engine).into_inner();
+        let mut t = u;
+        for _ in 1..iterations {
+            let mut engine = keyed.clone();
+            engine.input(&u);
+            u = Hmac::from_engine(engine).into_inner();
+            t.iter_mut().zip(u).for_each(|(t, u)| *t ^= u);
+        }
+        output.extend_from_slice(&t);
+    }
+    output.truncate(len);
+    output
+}
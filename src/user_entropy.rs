@@ -0,0 +1,212 @@
+use bdk::bitcoin::hashes::hex::FromHex;
+use bdk::bitcoin::hashes::{sha256, Hash};
+use bdk::bitcoin::secp256k1::rand::{thread_rng, RngCore};
+use bdk::keys::bip39::{Language, Mnemonic as BdkMnemonic, WordCount};
+
+use crate::{Error, Mnemonic};
+
+const CARD_RANKS: &str = "A23456789TJQK";
+const CARD_SUITS: &str = "CDHS";
+
+/// Where user supplied entropy comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntropySource {
+    /// Six sided dice rolls `1` to `6`, one character per roll.
+    D6,
+    /// Twenty sided dice rolls `1` to `20`, separated by whitespace.
+    D20,
+    /// Playing cards such as `AS`, `10H` or `td`, separated by whitespace. Each card must be
+    /// drawn from a full, reshuffled deck.
+    Cards,
+    /// Coin flips `H` or `T`, one character per flip.
+    CoinFlips,
+    /// Raw entropy as hex, used as is.
+    Hex,
+}
+
+impl EntropySource {
+    /// Return the number of symbols a single roll, draw or flip can take.
+    fn symbols(self) -> u32 {
+        match self {
+            EntropySource::D6 => 6,
+            EntropySource::D20 => 20,
+            EntropySource::Cards => 52,
+            EntropySource::CoinFlips => 2,
+            EntropySource::Hex => 16,
+        }
+    }
+}
+
+/// Entropy typed in by a user, for building a mnemonic without trusting a random number
+/// generator.
+///
+/// The input is reduced to a canonical text: dice rolls and coin flips are written without
+/// separators (`16625...`, `HTTH...`), d20 rolls and cards are separated by single spaces
+/// (`20 1 7`, `AS TH 2C`), and hex is lowercase. Except for hex, the mnemonic entropy is the
+/// start of the SHA256 hash of the canonical text, so anyone can check a mnemonic by
+/// hashing the text with any SHA256 tool and converting the entropy to words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserEntropy {
+    source: EntropySource,
+    canonical: String,
+    symbols: usize,
+}
+
+impl UserEntropy {
+    /// Parse `input` from `source` into its canonical text.
+    pub fn new(source: EntropySource, input: String) -> Result<Self, Error> {
+        let symbols: Vec<String> = match source {
+            EntropySource::D6 => input
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| match c {
+                    '1'..='6' => Ok(c.to_string()),
+                    c => Err(invalid_symbol(source, &c.to_string())),
+                })
+                .collect::<Result<_, _>>()?,
+            EntropySource::D20 => input
+                .split_whitespace()
+                .map(|roll| match roll.parse::<u8>() {
+                    Ok(roll @ 1..=20) => Ok(roll.to_string()),
+                    _ => Err(invalid_symbol(source, roll)),
+                })
+                .collect::<Result<_, _>>()?,
+            EntropySource::Cards => input
+                .split_whitespace()
+                .map(|card| canonical_card(card).ok_or_else(|| invalid_symbol(source, card)))
+                .collect::<Result<_, _>>()?,
+            EntropySource::CoinFlips => input
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| match c.to_ascii_uppercase() {
+                    c @ ('H' | 'T') => Ok(c.to_string()),
+                    _ => Err(invalid_symbol(source, &c.to_string())),
+                })
+                .collect::<Result<_, _>>()?,
+            EntropySource::Hex => input
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| {
+                    if c.is_ascii_hexdigit() {
+                        Ok(c.to_ascii_lowercase().to_string())
+                    } else {
+                        Err(invalid_symbol(source, &c.to_string()))
+                    }
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        let separator = match source {
+            EntropySource::D20 | EntropySource::Cards => " ",
+            _ => "",
+        };
+        if source == EntropySource::Hex && symbols.len() % 2 == 1 {
+            return Err(Error::InvalidEntropy(
+                "hex entropy must be a whole number of bytes".to_string(),
+            ));
+        }
+        Ok(Self {
+            source,
+            canonical: symbols.join(separator),
+            symbols: symbols.len(),
+        })
+    }
+
+    /// Return the source the entropy came from.
+    pub fn source(&self) -> EntropySource {
+        self.source
+    }
+
+    /// Return the canonical text the mnemonic entropy is derived from.
+    pub fn canonical(&self) -> String {
+        self.canonical.clone()
+    }
+
+    /// Return the number of whole bits of entropy in the input.
+    pub fn bits(&self) -> u32 {
+        (self.symbols as f64 * f64::from(self.source.symbols()).log2()).floor() as u32
+    }
+
+    /// Return the entropy for a mnemonic of `word_count` words, checking that the input
+    /// holds at least that many bits.
+    ///
+    /// Hex entropy must have exactly the length of the word count.
+    pub fn entropy(&self, word_count: WordCount) -> Result<Vec<u8>, Error> {
+        let required = word_count as u32;
+        if self.bits() < required {
+            return Err(Error::InsufficientEntropy {
+                required,
+                provided: self.bits(),
+            });
+        }
+        let len = required as usize / 8;
+        match self.source {
+            EntropySource::Hex if self.bits() != required => Err(Error::InvalidEntropy(format!(
+                "hex entropy is {} bytes but {} words need {}",
+                self.bits() / 8,
+                len * 3 / 4,
+                len
+            ))),
+            EntropySource::Hex => Ok(Vec::<u8>::from_hex(&self.canonical)
+                .expect("canonical hex is an even number of hex digits")),
+            _ => Ok(sha256::Hash::hash(self.canonical.as_bytes())[..len].to_vec()),
+        }
+    }
+
+    /// Build a mnemonic of `word_count` words in `language` from the entropy.
+    ///
+    /// `mix` is XORed into the entropy when given, for example bytes from
+    /// [`UserEntropy::os_randomness`], so the result is as strong as the better of the two
+    /// sources. Keep the mixed bytes to reproduce the mnemonic.
+    pub fn to_mnemonic(
+        &self,
+        language: Language,
+        word_count: WordCount,
+        mix: Option<Vec<u8>>,
+    ) -> Result<Mnemonic, Error> {
+        let mut entropy = self.entropy(word_count)?;
+        if let Some(mix) = mix {
+            if mix.len() != entropy.len() {
+                return Err(Error::InvalidEntropy(format!(
+                    "{} bytes to mix in but the entropy is {} bytes",
+                    mix.len(),
+                    entropy.len()
+                )));
+            }
+            entropy
+                .iter_mut()
+                .zip(mix)
+                .for_each(|(byte, mix)| *byte ^= mix);
+        }
+        let mnemonic = BdkMnemonic::from_entropy_in(language, &entropy)?;
+        Ok(Mnemonic { mnemonic })
+    }
+
+    /// Return OS random bytes of the entropy length of `word_count`, for mixing in.
+    pub fn os_randomness(word_count: WordCount) -> Vec<u8> {
+        let mut bytes = vec![0u8; word_count as usize / 8];
+        thread_rng().fill_bytes(&mut bytes);
+        bytes
+    }
+}
+
+fn canonical_card(card: &str) -> Option<String> {
+    if !card.is_ascii() {
+        return None;
+    }
+    let card = card.to_ascii_uppercase();
+    let (rank, suit) = card.split_at(card.len().checked_sub(1)?);
+    let rank = match rank {
+        "10" => "T",
+        rank if rank.len() == 1 && CARD_RANKS.contains(rank) => rank,
+        _ => return None,
+    };
+    if suit.len() != 1 || !CARD_SUITS.contains(suit) {
+        return None;
+    }
+    Some(format!("{}{}", rank, suit))
+}
+
+fn invalid_symbol(source: EntropySource, symbol: &str) -> Error {
+    Error::InvalidEntropy(format!("{:?} is not a valid {:?} symbol", symbol, source))
+}
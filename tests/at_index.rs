@@ -0,0 +1,97 @@
+mod common;
+
+use extended_keys_derive_rust::{DescriptorSecretKey, Error, Network, Wildcard};
+
+use common::{mnemonic, path};
+
+const WIF: &str = "cMpMxK92W1DjqDvWV3pMn4xLwAuQJhNF3MFqkEHUQRPQofUJku8R";
+
+fn receive_dsk() -> DescriptorSecretKey {
+    DescriptorSecretKey::new(Network::Testnet, &mnemonic(), None)
+        .unwrap()
+        .derive(&path("m/84h/1h/0h"))
+        .unwrap()
+        .extend(&path("m/0"))
+        .unwrap()
+}
+
+#[test]
+fn test_secret_key_at_index() {
+    let receive = receive_dsk();
+    let child = receive.at_index(17).unwrap();
+    assert_eq!(child.wildcard(), Wildcard::None);
+    assert!(child
+        .as_string()
+        .starts_with("[d1d04177/84'/1'/0'/0/17]tprv"));
+
+    let expected = receive
+        .derive(&path("m/17"))
+        .unwrap()
+        .with_wildcard(Wildcard::None)
+        .unwrap();
+    assert_eq!(child.as_string(), expected.as_string());
+}
+
+#[test]
+fn test_public_key_at_index() {
+    let receive = receive_dsk();
+    let child = receive.as_public().unwrap().at_index(17).unwrap();
+    assert!(child
+        .as_string()
+        .starts_with("[d1d04177/84'/1'/0'/0/17]tpub"));
+    assert_eq!(
+        child.as_string(),
+        receive
+            .at_index(17)
+            .unwrap()
+            .as_public()
+            .unwrap()
+            .as_string()
+    );
+}
+
+#[test]
+fn test_hardened_wildcard_at_index() {
+    let receive = receive_dsk().with_wildcard(Wildcard::Hardened).unwrap();
+    let child = receive.at_index(3).unwrap();
+    assert!(child
+        .as_string()
+        .starts_with("[d1d04177/84'/1'/0'/0/3']tprv"));
+}
+
+#[test]
+fn test_iter_range() {
+    let receive = receive_dsk().as_public().unwrap();
+    let children = receive
+        .iter_range(5..10)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(children.len(), 5);
+    for (index, child) in (5..10).zip(children) {
+        assert_eq!(
+            child.as_string(),
+            receive.at_index(index).unwrap().as_string()
+        );
+    }
+
+    let secret_children = receive_dsk().iter_range(0..2).count();
+    assert_eq!(secret_children, 2);
+}
+
+#[test]
+fn test_at_index_errors() {
+    let receive = receive_dsk();
+    let fixed = receive.with_wildcard(Wildcard::None).unwrap();
+    assert!(matches!(fixed.at_index(0), Err(Error::NonRangedKey)));
+    assert!(matches!(
+        fixed.as_public().unwrap().at_index(0),
+        Err(Error::NonRangedKey)
+    ));
+    assert!(matches!(receive.at_index(1 << 31), Err(Error::Bip32(_))));
+
+    let single = DescriptorSecretKey::from_string(Network::Testnet, WIF.to_string()).unwrap();
+    assert!(matches!(
+        single.at_index(0),
+        Err(Error::SingleKeyDerivation)
+    ));
+}
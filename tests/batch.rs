@@ -0,0 +1,130 @@
+mod common;
+
+use std::str::FromStr;
+
+use bdk::bitcoin::secp256k1::Secp256k1;
+use bdk::keys::DescriptorPublicKey as BdkDescriptorPublicKey;
+use bdk::miniscript::{Descriptor, DescriptorTrait};
+use extended_keys_derive_rust::{
+    DescriptorPublicKey, DescriptorSecretKey, Error, Network, ScriptType,
+};
+
+use common::{mnemonic, path};
+
+fn receive_dpk() -> DescriptorPublicKey {
+    let mnemonic = mnemonic();
+    DescriptorSecretKey::new(Network::Testnet, &mnemonic, None)
+        .unwrap()
+        .derive(&path("m/84h/1h/0h"))
+        .unwrap()
+        .as_public()
+        .unwrap()
+        .extend(&path("m/0"))
+        .unwrap()
+}
+
+#[test]
+fn test_derive_batch_matches_at_index() {
+    let receive = receive_dpk();
+    let expected: Vec<_> = receive.iter_range(1000..1600).map(Result::unwrap).collect();
+    for threads in [0, 1, 3, 4] {
+        let batch = receive.derive_batch(1000..1600, threads).unwrap();
+        assert_eq!(batch.len(), 600);
+        let derived: Vec<_> = batch.map(Result::unwrap).collect();
+        assert_eq!(derived, expected);
+    }
+    assert!(expected[0]
+        .as_string()
+        .starts_with("[d1d04177/84'/1'/0'/0/1000]tpub"));
+}
+
+#[test]
+fn test_derive_batch_streams() {
+    let receive = receive_dpk();
+    let mut batch = receive.derive_batch(0..u32::MAX, 2).unwrap();
+    assert_eq!(batch.next().unwrap().unwrap(), receive.at_index(0).unwrap());
+    assert_eq!(
+        batch.nth(9999).unwrap().unwrap(),
+        receive.at_index(10000).unwrap()
+    );
+    assert_eq!(batch.len(), u32::MAX as usize - 10001);
+
+    // Indices past the unhardened range fail one by one, like `at_index`.
+    let last: Vec<_> = receive
+        .derive_batch((1 << 31) - 1..(1 << 31) + 1, 2)
+        .unwrap()
+        .collect();
+    assert!(last[0].is_ok());
+    assert!(matches!(last[1], Err(Error::Bip32(_))));
+    assert_eq!(receive.derive_batch(5..5, 4).unwrap().count(), 0);
+
+    // A range ending at the top of the index space is split without overflowing.
+    let top: Vec<_> = receive
+        .derive_batch(u32::MAX - 101..u32::MAX, 2)
+        .unwrap()
+        .collect();
+    assert_eq!(top.len(), 101);
+    assert!(top.iter().all(|key| matches!(key, Err(Error::Bip32(_)))));
+}
+
+#[test]
+fn test_derive_batch_scripts() {
+    let secp = Secp256k1::verification_only();
+    let receive = receive_dpk();
+    let scripts: Vec<_> = receive
+        .derive_batch(0..20, 2)
+        .unwrap()
+        .map(|key| key?.script_pubkey(ScriptType::P2wpkh))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(scripts.len(), 20);
+    let descriptor =
+        Descriptor::<BdkDescriptorPublicKey>::from_str(&format!("wpkh({})", receive.as_string()))
+            .unwrap();
+    for (index, script) in (0..).zip(scripts) {
+        assert_eq!(
+            script,
+            descriptor
+                .derived_descriptor(&secp, index)
+                .unwrap()
+                .script_pubkey()
+        );
+    }
+
+    let first = receive.at_index(0).unwrap();
+    let descriptor =
+        Descriptor::<BdkDescriptorPublicKey>::from_str(&format!("pkh({})", first.as_string()))
+            .unwrap();
+    assert_eq!(
+        first.script_pubkey(ScriptType::P2pkh).unwrap(),
+        descriptor
+            .derived_descriptor(&secp, 0)
+            .unwrap()
+            .script_pubkey()
+    );
+
+    // A ranged key has no single script.
+    assert!(matches!(
+        receive.script_pubkey(ScriptType::P2wpkh),
+        Err(Error::InvalidKey(_))
+    ));
+}
+
+#[test]
+fn test_derive_batch_rejects_underivable_keys() {
+    let receive = receive_dpk();
+    let child = receive.at_index(0).unwrap();
+    assert!(matches!(
+        child.derive_batch(0..10, 1),
+        Err(Error::NonRangedKey)
+    ));
+    let single = DescriptorPublicKey::from_string(
+        Network::Testnet,
+        "02e6642fd69bd211f93f7f1f36ca51a26a5290eb2dd1b0d8279a87bb0d480c8443".to_string(),
+    )
+    .unwrap();
+    assert!(matches!(
+        single.derive_batch(0..10, 1),
+        Err(Error::SingleKeyDerivation)
+    ));
+}
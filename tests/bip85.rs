@@ -0,0 +1,118 @@
+use extended_keys_derive_rust::{
+    DerivationPath, DescriptorSecretKey, Error, Language, Network, WordCount,
+};
+
+// The master key of the BIP-85 test vectors.
+const MASTER: &str = "xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb";
+
+fn master() -> DescriptorSecretKey {
+    DescriptorSecretKey::from_string(Network::Bitcoin, MASTER.to_string()).unwrap()
+}
+
+#[test]
+fn test_bip39_vectors() {
+    let master = master();
+    for (word_count, expected) in [
+        (
+            WordCount::Words12,
+            "girl mad pet galaxy egg matter matrix prison refuse sense ordinary nose",
+        ),
+        (
+            WordCount::Words18,
+            "near account window bike charge season chef number sketch tomorrow excuse sniff circle vital hockey outdoor supply token",
+        ),
+        (
+            WordCount::Words24,
+            "puppy ocean match cereal symbol another shed magic wrap hammer bulb intact gadget divorce twin tonight reason outdoor destroy simple truth cigar social volcano",
+        ),
+    ] {
+        let mnemonic = master
+            .bip85_mnemonic(Language::English, word_count, 0)
+            .unwrap();
+        assert_eq!(mnemonic.as_string(), expected);
+    }
+
+    let first = master
+        .bip85_mnemonic(Language::English, WordCount::Words12, 1)
+        .unwrap();
+    assert_ne!(
+        first.as_string(),
+        "girl mad pet galaxy egg matter matrix prison refuse sense ordinary nose"
+    );
+    let japanese = master
+        .bip85_mnemonic(Language::Japanese, WordCount::Words12, 0)
+        .unwrap();
+    assert_eq!(japanese.language(), Language::Japanese);
+    assert_eq!(japanese.word_count(), 12);
+}
+
+#[test]
+fn test_key_vectors() {
+    let master = master();
+    assert_eq!(
+        master.bip85_wif(0).unwrap(),
+        "Kzyv4uF39d4Jrw2W7UryTHwZr1zQVNk4dAFyqE6BuMrMh1Za7uhp"
+    );
+    assert_eq!(
+        master.bip85_xprv(0).unwrap().as_string(),
+        "xprv9s21ZrQH143K2srSbCSg4m4kLvPMzcWydgmKEnMmoZUurYuBuYG46c6P71UGXMzmriLzCCBvKQWBUv3vPB3m1SATMhp3uEjXHJ42jFg7myX/*"
+    );
+
+    let testnet = DescriptorSecretKey::from_seed(Network::Testnet, vec![1u8; 32]).unwrap();
+    assert!(testnet.bip85_wif(0).unwrap().starts_with('c'));
+    assert!(testnet
+        .bip85_xprv(0)
+        .unwrap()
+        .as_string()
+        .starts_with("tprv"));
+}
+
+#[test]
+fn test_hex_and_password_vectors() {
+    let master = master();
+    assert_eq!(
+        master.bip85_hex(64, 0).unwrap(),
+        "492db4698cf3b73a5a24998aa3e9d7fa96275d85724a91e71aa2d645442f878555d078fd1f1f67e368976f04137b1f7a0d19232136ca50c44614af72b5582a5c"
+    );
+    assert_eq!(master.bip85_hex(16, 0).unwrap().len(), 32);
+    assert_eq!(
+        master.bip85_password_base64(21, 0).unwrap(),
+        "dKLoepugzdVJvdL56ogNV"
+    );
+    assert_eq!(master.bip85_password_base85(12, 0).unwrap(), "_s`{TW89)i4`");
+
+    for result in [
+        master.bip85_hex(15, 0),
+        master.bip85_hex(65, 0),
+        master.bip85_password_base64(19, 0),
+        master.bip85_password_base64(87, 0),
+        master.bip85_password_base85(9, 0),
+        master.bip85_password_base85(81, 0),
+    ] {
+        assert!(matches!(result, Err(Error::InvalidBip85(_))));
+    }
+}
+
+#[test]
+fn test_unsupported_keys() {
+    let master = master();
+    assert!(matches!(
+        master.bip85_hex(16, 1 << 31),
+        Err(Error::Bip32(_))
+    ));
+
+    let extended = master
+        .extend(&DerivationPath::new("m/0".to_string()).unwrap())
+        .unwrap();
+    assert!(matches!(
+        extended.bip85_wif(0),
+        Err(Error::PendingDerivationPath)
+    ));
+
+    let wif = DescriptorSecretKey::from_string(
+        Network::Testnet,
+        "cMpMxK92W1DjqDvWV3pMn4xLwAuQJhNF3MFqkEHUQRPQofUJku8R".to_string(),
+    )
+    .unwrap();
+    assert!(matches!(wif.bip85_wif(0), Err(Error::SingleKeyDerivation)));
+}
@@ -0,0 +1,174 @@
+use bdk::bitcoin::hashes::hex::{FromHex, ToHex};
+use extended_keys_derive_rust::{
+    Codex32, DescriptorKey, DescriptorSecretKey, Error, Mnemonic, Network,
+};
+
+fn codex32(string: &str) -> Codex32 {
+    Codex32::from_string(string.to_string()).unwrap()
+}
+
+fn invalid(string: &str) -> bool {
+    matches!(
+        Codex32::from_string(string.to_string()),
+        Err(Error::InvalidCodex32(_))
+    )
+}
+
+// The test vectors of BIP-93.
+#[test]
+fn test_unshared_seed() {
+    let seed = codex32("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw");
+    assert_eq!(seed.threshold(), 0);
+    assert_eq!(seed.identifier(), "test");
+    assert_eq!(seed.share_index(), 's');
+    assert_eq!(
+        seed.master_seed().unwrap().to_hex(),
+        "318c6318c6318c6318c6318c6318c631"
+    );
+
+    let seed = Vec::<u8>::from_hex(&"ffeeddccbbaa99887766554433221100".repeat(2)).unwrap();
+    let encoded = Codex32::from_seed("leet".to_string(), seed.clone()).unwrap();
+    assert_eq!(
+        encoded.as_string(),
+        "ms10leetsllhdmn9m42vcsamx24zrxgs3qrl7ahwvhw4fnzrhve25gvezzyqqtum9pgv99ycma"
+    );
+    // Padding bits other than zero are accepted.
+    let other_padding =
+        codex32("ms10leetsllhdmn9m42vcsamx24zrxgs3qrl7ahwvhw4fnzrhve25gvezzyq0pgjxpzx0ysaam");
+    assert_eq!(other_padding.master_seed().unwrap(), seed);
+
+    let long = codex32("MS100C8VSM32ZXFGUHPCHTLUPZRY9X8GF2TVDW0S3JN54KHCE6MUA7LQPZYGSFJD6AN074RXVCEMLH8WU3TK925ACDEFGHJKLMNPQRSTUVWXY06FHPV80UNDVARHRAK");
+    let long_seed = long.master_seed().unwrap();
+    assert_eq!(
+        long_seed.to_hex(),
+        "dc5423251cb87175ff8110c8531d0952d8d73e1194e95b5f19d6f9df7c01111104c9baecdfea8cccc677fb9ddc8aec5553b86e528bcadfdcc201c17c638c47e9"
+    );
+    let encoded = Codex32::from_seed("0c8v".to_string(), long_seed.clone()).unwrap();
+    assert_eq!(encoded.as_string().len(), 127);
+    assert_eq!(encoded.master_seed().unwrap(), long_seed);
+}
+
+#[test]
+fn test_interpolation() {
+    let shares = vec![
+        codex32("MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM"),
+        codex32("MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN"),
+    ];
+    assert_eq!(
+        Codex32::interpolate(shares.clone(), 'd')
+            .unwrap()
+            .as_string(),
+        "ms12namedll4f8jlh4e5vdvuldlfxu2jhdnlsm97xvenrxeg"
+    );
+    let seed = Codex32::combine(shares).unwrap();
+    assert_eq!(
+        seed.as_string(),
+        "ms12names6xqguzttxkeqnjsjzv4jv3nz5k3kwgsphuh6evw"
+    );
+    assert_eq!(
+        seed.master_seed().unwrap().to_hex(),
+        "d1808e096b35b209ca12132b264662a5"
+    );
+
+    let shares = vec![
+        codex32("ms13cashsllhdmn9m42vcsamx24zrxgs3qqjzqud4m0d6nln"),
+        codex32("ms13casha320zyxwvutsrqpnmlkjhgfedca2a8d0zehn8a0t"),
+        codex32("ms13cashcacdefghjklmnpqrstuvwxyz023949xq35my48dr"),
+    ];
+    for (index, expected) in [
+        ('d', "ms13cashd0wsedstcdcts64cd7wvy4m90lm28w4ffupqs7rm"),
+        ('e', "ms13casheekgpemxzshcrmqhaydlp6yhms3ws7320xyxsar9"),
+        ('f', "ms13cashf8jh6sdrkpyrsp5ut94pj8ktehhw2hfvyrj48704"),
+    ] {
+        let share = Codex32::interpolate(shares.clone(), index).unwrap();
+        assert_eq!(share.as_string(), expected);
+    }
+
+    // Any three shares recover the seed.
+    let derived = vec![
+        codex32("ms13cashd0wsedstcdcts64cd7wvy4m90lm28w4ffupqs7rm"),
+        codex32("ms13casheekgpemxzshcrmqhaydlp6yhms3ws7320xyxsar9"),
+        codex32("ms13cashf8jh6sdrkpyrsp5ut94pj8ktehhw2hfvyrj48704"),
+    ];
+    assert_eq!(
+        Codex32::combine(derived.clone())
+            .unwrap()
+            .master_seed()
+            .unwrap()
+            .to_hex(),
+        "ffeeddccbbaa99887766554433221100"
+    );
+    assert!(matches!(
+        Codex32::combine(derived[..2].to_vec()),
+        Err(Error::InvalidShares(_))
+    ));
+}
+
+#[test]
+fn test_split_and_combine_into_key() {
+    let mnemonic = Mnemonic::from_entropy(vec![0u8; 16]).unwrap();
+    let seed = mnemonic.seed(Some("TREZOR".to_string()));
+    let expected = DescriptorSecretKey::new(Network::Bitcoin, &mnemonic, Some("TREZOR".into()))
+        .unwrap()
+        .as_string();
+
+    let shares = Codex32::split(seed.clone(), "tRez".to_string(), 3, 5).unwrap();
+    assert_eq!(shares.len(), 5);
+    assert!(shares
+        .iter()
+        .all(|share| share.threshold() == 3 && share.identifier() == "trez"));
+    assert_eq!(
+        shares.iter().map(Codex32::share_index).collect::<String>(),
+        "acdef"
+    );
+    assert_eq!(shares[0].as_string().len(), 127);
+
+    let strings = |shares: &[Codex32]| shares.iter().map(Codex32::as_string).collect();
+    let key = DescriptorSecretKey::from_codex32(Network::Bitcoin, strings(&shares[2..])).unwrap();
+    assert_eq!(key.as_string(), expected);
+    let picked = vec![shares[4].clone(), shares[0].clone(), shares[3].clone()];
+    let key = DescriptorKey::from_codex32(Network::Bitcoin, strings(&picked)).unwrap();
+    assert_eq!(key.as_string(), expected);
+
+    let unshared = Codex32::from_seed("trez".to_string(), seed).unwrap();
+    let key =
+        DescriptorSecretKey::from_codex32(Network::Bitcoin, vec![unshared.as_string()]).unwrap();
+    assert_eq!(key.as_string(), expected);
+
+    assert!(matches!(
+        shares[0].master_seed(),
+        Err(Error::InvalidShares(_))
+    ));
+    assert!(Codex32::split(vec![1u8; 16], "test".to_string(), 1, 3).is_err());
+    assert!(Codex32::split(vec![1u8; 16], "test".to_string(), 3, 2).is_err());
+    assert!(Codex32::split(vec![1u8; 48], "test".to_string(), 2, 3).is_err());
+    assert!(Codex32::split(vec![1u8; 16], "tes".to_string(), 2, 3).is_err());
+    assert!(Codex32::split(vec![1u8; 16], "test".to_string(), 2, 31).is_ok());
+}
+
+#[test]
+fn test_invalid_strings() {
+    // Bad checksums.
+    assert!(invalid("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxxxxmazxdp4sx5q"));
+    assert!(invalid("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxxx4nknfgj6u67a"));
+    assert!(invalid("ms10testsyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyyymjljntsznrq3mv"));
+    // Short checksum on a long string and the other way around.
+    assert!(invalid("ms10testsxxxxxxxxxxxxxxxxxxxxxxxx372x3mkc5m8sa0q"));
+    assert!(invalid("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxncdn5kjxq7grt"));
+    // Improper lengths.
+    assert!(invalid("ms10testsxxxxxxxxxxxxxxxxxxxxxxxx8ty2gx0n6rnaa"));
+    assert!(invalid("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxxxc8d60uanwukvn"));
+    assert!(invalid("ms12testxxxxxxxxxxxxxxxxxxxxxxxxxxxxqmufxffdkzfac"));
+    // Share index other than s without a threshold, and a threshold of 1.
+    assert!(invalid("ms10testxxxxxxxxxxxxxxxxxxxxxxxxxxxx3wq9mzgrwag9"));
+    assert!(invalid("ms1testxxxxxxxxxxxxxxxxxxxxxxxxxxxxs9lz3we7s9wh4"));
+    // Mixed case.
+    for string in [
+        "MS10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw",
+        "ms10testSxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw",
+        "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4NZVCA9CMCZLW",
+    ] {
+        assert!(invalid(string));
+    }
+    assert!(!invalid("MS10TESTSXXXXXXXXXXXXXXXXXXXXXXXXXX4NZVCA9CMCZLW"));
+}
@@ -0,0 +1,24 @@
+//! Keys and helpers shared by the integration tests. Each test crate uses a different subset.
+#![allow(dead_code)]
+
+use extended_keys_derive_rust::{DerivationPath, DescriptorSecretKey, Mnemonic, Network};
+
+/// The mnemonic most tests derive from, whose master fingerprint is `d1d04177`.
+pub const MNEMONIC: &str = "chaos fabric time speed sponsor all flat solution wisdom trophy crack object robot pave observe combine where aware bench orient secret primary cable detect";
+
+/// The seed of the first BIP39 test vector, with passphrase "TREZOR", and its master key.
+pub const TREZOR_SEED: &str = "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04";
+pub const TREZOR_XPRV: &str = "xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF";
+
+pub fn mnemonic() -> Mnemonic {
+    Mnemonic::from_string(MNEMONIC.to_string()).unwrap()
+}
+
+/// The testnet master key of [`MNEMONIC`].
+pub fn master_dsk() -> DescriptorSecretKey {
+    DescriptorSecretKey::new(Network::Testnet, &mnemonic(), None).unwrap()
+}
+
+pub fn path(path: &str) -> DerivationPath {
+    DerivationPath::new(path.to_string()).unwrap()
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use extended_keys_derive_rust::{
+    DerivationCache, DerivationCacheStats, DescriptorSecretKey, Error, Network, PendingPathPolicy,
+};
+
+use common::{master_dsk, mnemonic, path};
+
+#[test]
+fn test_cache_matches_derive() {
+    let master = master_dsk();
+    let cache = DerivationCache::new(100);
+    for index in 0..5 {
+        let path = path(&format!("m/84h/1h/0h/0/{}", index));
+        assert!(cache.derive(&master, &path).unwrap() == master.derive(&path).unwrap());
+    }
+    assert_eq!(
+        cache.stats(),
+        DerivationCacheStats {
+            hits: 4,
+            misses: 1,
+            evictions: 0,
+            // m/84h, m/84h/1h, m/84h/1h/0h, m/84h/1h/0h/0 and the five children.
+            len: 9,
+        }
+    );
+
+    // A pending path is folded in before the cached prefix is looked up.
+    let account = master.derive(&path("m/84h/1h/0h")).unwrap();
+    let receive = account.extend(&path("m/0")).unwrap();
+    assert!(cache.derive(&receive, &path("m/7")).unwrap() == receive.derive(&path("m/7")).unwrap());
+    assert!(matches!(
+        cache.derive_with_policy(&receive, &path("m/7"), PendingPathPolicy::Reject),
+        Err(Error::PendingDerivationPath)
+    ));
+
+    // The same seed on another network reuses the entries but keeps its own network, and
+    // another root key never shares entries.
+    let mainnet = DescriptorSecretKey::new(Network::Bitcoin, &mnemonic(), None).unwrap();
+    let path = path("m/84h/1h/0h/0/1");
+    assert!(cache.derive(&mainnet, &path).unwrap() == mainnet.derive(&path).unwrap());
+    assert!(cache.derive(&account, &path).unwrap() == account.derive(&path).unwrap());
+}
+
+#[test]
+fn test_cache_evicts_least_recently_used() {
+    let master = master_dsk();
+    let cache = DerivationCache::new(4);
+    cache.derive(&master, &path("m/84h/1h/0h/0")).unwrap();
+    cache.derive(&master, &path("m/84h/1h/0h/1")).unwrap();
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses), (1, 1));
+    assert_eq!((stats.evictions, stats.len), (1, 4));
+
+    // m/84h was evicted, so only m/84h/1h/0h and below can still be reused.
+    cache.derive(&master, &path("m/84h/1h/0h/2")).unwrap();
+    cache.derive(&master, &path("m/84h/2h")).unwrap();
+    assert_eq!(cache.stats().misses, 2);
+
+    cache.clear();
+    assert_eq!(cache.stats().len, 0);
+    cache.derive(&master, &path("m/84h/1h/0h/0")).unwrap();
+    assert_eq!(cache.stats().misses, 3);
+}
+
+#[test]
+fn test_cache_without_capacity() {
+    let master = master_dsk();
+    let cache = DerivationCache::new(0);
+    let path = path("m/84h/1h/0h/0/5");
+    for _ in 0..2 {
+        assert!(cache.derive(&master, &path).unwrap() == master.derive(&path).unwrap());
+    }
+    assert_eq!(
+        cache.stats(),
+        DerivationCacheStats {
+            misses: 2,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_cache_shared_between_threads() {
+    let master = master_dsk();
+    let cache = DerivationCache::new(1000);
+    std::thread::scope(|scope| {
+        for thread in 0..4 {
+            let (master, cache) = (&master, &cache);
+            scope.spawn(move || {
+                for index in 0..10 {
+                    let path = path(&format!("m/84h/1h/0h/{}/{}", thread % 2, index));
+                    assert!(cache.derive(master, &path).unwrap() == master.derive(&path).unwrap());
+                }
+            });
+        }
+    });
+    let stats = cache.stats();
+    assert_eq!(stats.hits + stats.misses, 40);
+    assert_eq!(stats.len, 3 + 2 + 20);
+}
@@ -0,0 +1,143 @@
+//! Every derive/extend ordering from the demo in `src/main.rs`, for each wrapper and policy.
+
+mod common;
+
+use extended_keys_derive_rust::{DescriptorKey, Error, Network, PendingPathPolicy};
+
+use common::{master_dsk, mnemonic, path};
+
+const MASTER_TPRV: &str = "tprv8ZgxMBicQKsPdWuqM1t1CDRvQtQuBPyfL6GbhQwtxDKgUAVPbxmj71pRA8raTqLrec5LyTs5TqCxdABcZr77bt2KyWA5bizJHnC4g4ysm4h";
+const M0_TPRV: &str = "tprv8d7Y4JLmD25jkKbyDZXcdoPHu1YtMHuH21qeN7mFpjfumtSU7eZimFYUCSa3MYzkEYfSNRBV34GEr2QXwZCMYRZ7M1g6PUtiLhbJhBZEGYJ";
+
+fn master_dk() -> DescriptorKey {
+    DescriptorKey::new(Network::Testnet, &mnemonic(), None).unwrap()
+}
+
+#[test]
+fn test_secret_key_orderings() {
+    let master = master_dsk();
+    assert_eq!(master.as_string(), format!("{}/*", MASTER_TPRV));
+
+    let derived = master.derive(&path("m/0")).unwrap();
+    assert_eq!(derived.as_string(), format!("[d1d04177/0]{}/*", M0_TPRV));
+
+    let extended = master.extend(&path("m/0")).unwrap();
+    assert_eq!(extended.as_string(), format!("{}/0/*", MASTER_TPRV));
+
+    let derived_extended = derived.extend(&path("m/0")).unwrap();
+    assert_eq!(
+        derived_extended.as_string(),
+        format!("[d1d04177/0]{}/0/*", M0_TPRV)
+    );
+
+    let extended_extended = extended.extend(&path("m/0")).unwrap();
+    assert_eq!(
+        extended_extended.as_string(),
+        format!("{}/0/0/*", MASTER_TPRV)
+    );
+
+    let extended_derived = extended.derive(&path("m/0")).unwrap();
+    let derived_twice = master.derive(&path("m/0/0")).unwrap();
+    assert!(extended_derived.as_string().starts_with("[d1d04177/0/0]"));
+    assert_eq!(extended_derived.as_string(), derived_twice.as_string());
+
+    let folded = extended
+        .derive_with_policy(&path("m/0"), PendingPathPolicy::Fold)
+        .unwrap();
+    assert_eq!(folded.as_string(), extended_derived.as_string());
+    assert!(matches!(
+        extended.derive_with_policy(&path("m/0"), PendingPathPolicy::Reject),
+        Err(Error::PendingDerivationPath)
+    ));
+
+    // without a pending path both policies agree
+    let rejected = master
+        .derive_with_policy(&path("m/0"), PendingPathPolicy::Reject)
+        .unwrap();
+    assert_eq!(rejected.as_string(), derived.as_string());
+}
+
+#[test]
+fn test_public_key_orderings() {
+    let master = master_dsk().as_public().unwrap();
+    let derived = master.derive(&path("m/0")).unwrap();
+    let extended = master.extend(&path("m/0")).unwrap();
+
+    let derived_extended = derived.extend(&path("m/0")).unwrap();
+    assert!(derived_extended.as_string().starts_with("[d1d04177/0]tpub"));
+    assert!(derived_extended.as_string().ends_with("/0/*"));
+
+    let extended_derived = extended.derive(&path("m/0")).unwrap();
+    let derived_twice = master.derive(&path("m/0/0")).unwrap();
+    assert_eq!(extended_derived.as_string(), derived_twice.as_string());
+    assert!(matches!(
+        extended.derive_with_policy(&path("m/0"), PendingPathPolicy::Reject),
+        Err(Error::PendingDerivationPath)
+    ));
+
+    // a public key cannot get a hardened pending path in the first place
+    assert!(matches!(
+        master.extend(&path("m/0h")),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+}
+
+#[test]
+fn test_descriptor_key_orderings() {
+    let master = master_dk();
+    let derived = master.derive(Some(&path("m/0")), None).unwrap();
+    let extended = master.derive(None, Some(&path("m/0"))).unwrap();
+    assert_eq!(extended.as_string(), format!("{}/0/*", MASTER_TPRV));
+
+    let derived_extended = derived.derive(None, Some(&path("m/0"))).unwrap();
+    assert_eq!(
+        derived_extended.as_string(),
+        format!("[d1d04177/0]{}/0/*", M0_TPRV)
+    );
+
+    let extended_extended = extended.derive(None, Some(&path("m/0"))).unwrap();
+    assert_eq!(
+        extended_extended.as_string(),
+        format!("{}/0/0/*", MASTER_TPRV)
+    );
+
+    let extended_derived = extended.derive(Some(&path("m/0")), None).unwrap();
+    let derived_twice = master.derive(Some(&path("m/0/0")), None).unwrap();
+    assert_eq!(extended_derived.as_string(), derived_twice.as_string());
+    assert!(matches!(
+        extended.derive_with_policy(Some(&path("m/0")), None, PendingPathPolicy::Reject),
+        Err(Error::PendingDerivationPath)
+    ));
+
+    // the wrappers agree on every ordering
+    let master_dsk = master_dsk();
+    let secret_derived = master_dsk.derive(&path("m/0")).unwrap();
+    let secret_extended = master_dsk.extend(&path("m/0")).unwrap();
+    for (key, secret_key) in [
+        (&derived, &secret_derived),
+        (&extended, &secret_extended),
+        (
+            &derived_extended,
+            &secret_derived.extend(&path("m/0")).unwrap(),
+        ),
+        (
+            &extended_extended,
+            &secret_extended.extend(&path("m/0")).unwrap(),
+        ),
+        (
+            &extended_derived,
+            &secret_extended.derive(&path("m/0")).unwrap(),
+        ),
+    ] {
+        assert_eq!(key.as_string(), secret_key.as_string());
+    }
+
+    // deriving and extending in one call is deriving, then extending
+    assert_eq!(
+        master
+            .derive(Some(&path("m/0")), Some(&path("m/0")))
+            .unwrap()
+            .as_string(),
+        derived_extended.as_string()
+    );
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use extended_keys_derive_rust::{
+    DerivationPath, DescriptorKey, DescriptorPublicKey, Error, Mnemonic, Network,
+};
+
+use common::{master_dsk, mnemonic, path, MNEMONIC};
+
+#[test]
+fn test_secret_key_api() {
+    let master_dsk = master_dsk();
+    assert_eq!(master_dsk.as_string(), "tprv8ZgxMBicQKsPdWuqM1t1CDRvQtQuBPyfL6GbhQwtxDKgUAVPbxmj71pRA8raTqLrec5LyTs5TqCxdABcZr77bt2KyWA5bizJHnC4g4ysm4h/*");
+
+    let derived_dsk = master_dsk.derive(&path("m/0")).unwrap();
+    assert_eq!(derived_dsk.as_string(), "[d1d04177/0]tprv8d7Y4JLmD25jkKbyDZXcdoPHu1YtMHuH21qeN7mFpjfumtSU7eZimFYUCSa3MYzkEYfSNRBV34GEr2QXwZCMYRZ7M1g6PUtiLhbJhBZEGYJ/*");
+
+    let extended_dsk = derived_dsk.extend(&path("m/0")).unwrap();
+    assert_eq!(extended_dsk.as_string(), "[d1d04177/0]tprv8d7Y4JLmD25jkKbyDZXcdoPHu1YtMHuH21qeN7mFpjfumtSU7eZimFYUCSa3MYzkEYfSNRBV34GEr2QXwZCMYRZ7M1g6PUtiLhbJhBZEGYJ/0/*");
+}
+
+#[test]
+fn test_public_key_api() {
+    let master_dpk: DescriptorPublicKey = master_dsk().as_public().unwrap();
+    assert_eq!(master_dpk.as_string(), "tpubD6NzVbkrYhZ4WywdEfYbbd62yuvqLjAZuPsNyvzCNV85JekAEMbKHWSHLF9h3j45SxewXDcLv328B1SEZrxg4iwGfmdt1pDFjZiTkGiFqGa/*");
+
+    let derived_dpk = master_dpk.derive(&path("m/0")).unwrap();
+    assert_eq!(derived_dpk.as_string(), "[d1d04177/0]tpubD9oaCiP1MPmQdndm7DCD3D3QU34pWd6BbKSRedoZF1UJcNhEk3PJwkALNYkhxeTKL29oGNR7psqvT1KZydCGqUDEKXN6dVQJY2R8ooLPy8m/*");
+
+    let extended_dpk = master_dpk.extend(&path("m/0")).unwrap();
+    assert_eq!(extended_dpk.as_string(), "tpubD6NzVbkrYhZ4WywdEfYbbd62yuvqLjAZuPsNyvzCNV85JekAEMbKHWSHLF9h3j45SxewXDcLv328B1SEZrxg4iwGfmdt1pDFjZiTkGiFqGa/0/*");
+
+    assert!(matches!(
+        master_dpk.derive(&path("m/84h")),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+    assert!(matches!(
+        master_dpk.extend(&path("m/0h")),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+}
+
+#[test]
+fn test_descriptor_key_api() {
+    let master_dk = DescriptorKey::new(Network::Testnet, &mnemonic(), None).unwrap();
+    assert!(master_dk.is_secret());
+    assert_eq!(master_dk.as_string(), master_dsk().as_string());
+
+    let derived_dk = master_dk.derive(Some(&path("m/0")), None).unwrap();
+    assert_eq!(derived_dk.as_string(), "[d1d04177/0]tprv8d7Y4JLmD25jkKbyDZXcdoPHu1YtMHuH21qeN7mFpjfumtSU7eZimFYUCSa3MYzkEYfSNRBV34GEr2QXwZCMYRZ7M1g6PUtiLhbJhBZEGYJ/*");
+
+    let extended_dk = master_dk.derive(None, Some(&path("m/0"))).unwrap();
+    assert_eq!(extended_dk.as_string(), "tprv8ZgxMBicQKsPdWuqM1t1CDRvQtQuBPyfL6GbhQwtxDKgUAVPbxmj71pRA8raTqLrec5LyTs5TqCxdABcZr77bt2KyWA5bizJHnC4g4ysm4h/0/*");
+
+    let public_dk = derived_dk.as_public().unwrap();
+    assert!(!public_dk.is_secret());
+    assert_eq!(public_dk.as_string(), "[d1d04177/0]tpubD9oaCiP1MPmQdndm7DCD3D3QU34pWd6BbKSRedoZF1UJcNhEk3PJwkALNYkhxeTKL29oGNR7psqvT1KZydCGqUDEKXN6dVQJY2R8ooLPy8m/*");
+}
+
+#[test]
+fn test_descriptor_key_from_wrappers() {
+    let master_dsk = master_dsk();
+
+    let secret_dk = DescriptorKey::try_from(&master_dsk).unwrap();
+    assert!(secret_dk.is_secret());
+    assert_eq!(secret_dk.as_string(), master_dsk.as_string());
+
+    let master_dpk = master_dsk.as_public().unwrap();
+    let public_dk = DescriptorKey::try_from(&master_dpk).unwrap();
+    assert!(!public_dk.is_secret());
+    assert_eq!(public_dk.as_string(), master_dpk.as_string());
+}
+
+#[test]
+fn test_descriptor_key_debug_redacts_secrets() {
+    let master_dk = DescriptorKey::new(Network::Testnet, &mnemonic(), None).unwrap();
+    let debug = format!("{:?}", master_dk);
+    assert!(debug.contains("d1d04177"));
+    assert!(!debug.contains("tprv"));
+
+    let public_dk = master_dk.as_public().unwrap();
+    assert!(format!("{:?}", public_dk).contains(&public_dk.as_string()));
+}
+
+#[test]
+fn test_invalid_mnemonic() {
+    let mnemonic = MNEMONIC.replace("chaos", "chaoss");
+    let result = Mnemonic::from_string(mnemonic);
+    assert!(matches!(result, Err(Error::InvalidMnemonic(_))));
+}
+
+#[test]
+fn test_invalid_derivation_path() {
+    let result = DerivationPath::new("m/0/x".to_string());
+    assert!(matches!(result, Err(Error::InvalidDerivationPath(_))));
+}
+
+#[test]
+fn test_descriptor_key_hardened_from_public() {
+    let master_dk = DescriptorKey::new(Network::Testnet, &mnemonic(), None).unwrap();
+    let public_dk = master_dk.as_public().unwrap();
+    assert!(matches!(
+        public_dk.derive(Some(&path("m/84h")), None),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+    assert!(matches!(
+        public_dk.derive(None, Some(&path("m/0h"))),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+}
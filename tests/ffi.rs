@@ -0,0 +1,48 @@
+#![cfg(feature = "ffi")]
+
+mod common;
+
+use std::sync::Arc;
+
+use extended_keys_derive_rust::ffi::{
+    DerivationPath, DescriptorKey, DescriptorSecretKey, Mnemonic,
+};
+use extended_keys_derive_rust::Network;
+
+use common::MNEMONIC;
+
+fn path(path: &str) -> Arc<DerivationPath> {
+    Arc::new(DerivationPath::new(path.to_string()).unwrap())
+}
+
+#[test]
+fn test_ffi_handles_match_values() {
+    let mnemonic = Arc::new(Mnemonic::from_string(MNEMONIC.to_string()).unwrap());
+    let master = DescriptorSecretKey::new(Network::Testnet, mnemonic.clone(), None).unwrap();
+    let receive = master
+        .derive(path("m/84h/1h/0h"))
+        .unwrap()
+        .extend(path("m/0"))
+        .unwrap();
+    let expected = extended_keys_derive_rust::DescriptorSecretKey::new(
+        Network::Testnet,
+        mnemonic.value(),
+        None,
+    )
+    .unwrap()
+    .derive(path("m/84h/1h/0h").value())
+    .unwrap()
+    .extend(path("m/0").value())
+    .unwrap();
+    assert!(receive.value() == &expected);
+
+    let public = receive.as_public().unwrap();
+    let batch = public.derive_batch(0, 10, 2).unwrap();
+    assert_eq!(batch.len(), 10);
+    assert_eq!(batch[7], public.at_index(7).unwrap());
+
+    let key = DescriptorKey::new(Network::Testnet, mnemonic, None).unwrap();
+    let account = key.derive(Some(path("m/84h/1h/0h")), None).unwrap();
+    assert!(account.is_secret());
+    assert!(!account.as_public().unwrap().is_secret());
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use extended_keys_derive_rust::{
+    DerivationPath, DescriptorKey, DescriptorPublicKey, DescriptorSecretKey, Error, Network,
+};
+
+use common::mnemonic;
+
+fn account_dsk() -> DescriptorSecretKey {
+    let master = DescriptorSecretKey::new(Network::Testnet, &mnemonic(), None).unwrap();
+    let path = DerivationPath::new("m/84h/1h/0h".to_string()).unwrap();
+    master.derive(&path).unwrap()
+}
+
+#[test]
+fn test_secret_key_round_trip() {
+    let account = account_dsk();
+    let extended = account
+        .extend(&DerivationPath::new("m/0".to_string()).unwrap())
+        .unwrap();
+    let parsed = DescriptorSecretKey::from_string(Network::Testnet, extended.as_string()).unwrap();
+    assert_eq!(parsed.as_string(), extended.as_string());
+    assert!(parsed.as_string().starts_with("[d1d04177/84'/1'/0']tprv"));
+    assert!(parsed.as_string().ends_with("/0/*"));
+}
+
+#[test]
+fn test_public_key_round_trip() {
+    let account = account_dsk().as_public().unwrap();
+    let parsed = DescriptorPublicKey::from_string(Network::Regtest, account.as_string()).unwrap();
+    assert_eq!(parsed.as_string(), account.as_string());
+
+    // `h` and `'` hardened markers are equivalent
+    let with_h = account.as_string().replace('\'', "h");
+    let parsed = DescriptorPublicKey::from_string(Network::Testnet, with_h).unwrap();
+    assert_eq!(parsed.as_string(), account.as_string());
+}
+
+#[test]
+fn test_descriptor_key_round_trip() {
+    let secret = account_dsk();
+    let parsed = DescriptorKey::from_string(Network::Testnet, secret.as_string()).unwrap();
+    assert!(parsed.is_secret());
+    assert_eq!(parsed.as_string(), secret.as_string());
+
+    let public = secret.as_public().unwrap();
+    let parsed = DescriptorKey::from_string(Network::Testnet, public.as_string()).unwrap();
+    assert!(!parsed.is_secret());
+    assert_eq!(parsed.as_string(), public.as_string());
+}
+
+#[test]
+fn test_descriptor_key_xpub_containing_prv() {
+    // The base58 of this xpub, derived at m/84h/1h/0h/3681, happens to contain "prv".
+    let key = "[d1d04177/84'/1'/0'/3681]tpubDFA3tN2RXMYgxv7657g2JDNVErArMaYYBVxYoRZSRegVXvDQUEvng68XQEMUzovM1s4h7VsgeCXFFyW4ircLvo8prvT4AERrvL6CSwsAE8f/*";
+    let public = account_dsk()
+        .as_public()
+        .unwrap()
+        .derive(&DerivationPath::new("m/3681".to_string()).unwrap())
+        .unwrap();
+    assert_eq!(public.as_string(), key);
+    let parsed = DescriptorKey::from_string(Network::Testnet, key.to_string()).unwrap();
+    assert!(!parsed.is_secret());
+    assert_eq!(parsed.as_string(), key);
+}
+
+#[test]
+fn test_network_mismatch() {
+    let account = account_dsk();
+    assert!(matches!(
+        DescriptorSecretKey::from_string(Network::Bitcoin, account.as_string()),
+        Err(Error::NetworkMismatch { .. })
+    ));
+    assert!(matches!(
+        DescriptorKey::from_string(Network::Bitcoin, account.as_public().unwrap().as_string()),
+        Err(Error::NetworkMismatch { .. })
+    ));
+    // a secret key is not retried as a public key
+    assert!(matches!(
+        DescriptorKey::from_string(Network::Bitcoin, account.as_string()),
+        Err(Error::NetworkMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_origin_must_match_depth() {
+    let account = account_dsk().as_public().unwrap().as_string();
+    let short_origin = account.replace("[d1d04177/84'/1'/0']", "[d1d04177/84'/1']");
+    assert!(matches!(
+        DescriptorPublicKey::from_string(Network::Testnet, short_origin),
+        Err(Error::InvalidOrigin(_))
+    ));
+
+    let master = DescriptorSecretKey::new(Network::Testnet, &mnemonic(), None)
+        .unwrap()
+        .as_string();
+    assert!(matches!(
+        DescriptorSecretKey::from_string(Network::Testnet, format!("[deadbeef]{}", master)),
+        Err(Error::InvalidOrigin(_))
+    ));
+    assert!(
+        DescriptorSecretKey::from_string(Network::Testnet, format!("[d1d04177]{}", master)).is_ok()
+    );
+}
+
+#[test]
+fn test_public_key_rejects_hardened_steps() {
+    let account = account_dsk().as_public().unwrap().as_string();
+    let hardened_path = account.replace("/*", "/0h/*");
+    assert!(matches!(
+        DescriptorPublicKey::from_string(Network::Testnet, hardened_path),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+
+    let hardened_wildcard = account.replace("/*", "/*h");
+    assert!(matches!(
+        DescriptorPublicKey::from_string(Network::Testnet, hardened_wildcard),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+}
+
+#[test]
+fn test_malformed_keys() {
+    for key in [
+        "",
+        "tpubnotakey",
+        "[d1d04177/84'/1'/0'",
+        "[d1d0417]tpub/0/*/1",
+    ] {
+        assert!(DescriptorKey::from_string(Network::Testnet, key.to_string()).is_err());
+    }
+    let account = account_dsk().as_string();
+    let wildcard_not_last = account.replace("/*", "/*/0");
+    assert!(matches!(
+        DescriptorSecretKey::from_string(Network::Testnet, wildcard_not_last),
+        Err(Error::InvalidKey(_))
+    ));
+}
@@ -0,0 +1,133 @@
+use bdk::bitcoin::hashes::hex::FromHex;
+use bdk::bitcoin::util::bip32::ExtendedPrivKey;
+use bdk::keys::bip39;
+use extended_keys_derive_rust::{
+    DescriptorSecretKey, Error, Language, Mnemonic, Network, WordCount,
+};
+
+const JAPANESE_PASSPHRASE: &str = "㍍ガバヴァぱばぐゞちぢ十人十色";
+
+// (entropy, mnemonic, seed) from the Japanese BIP39 test vectors.
+const JAPANESE_VECTORS: [(&str, &str, &str); 4] = [
+    (
+        "00000000000000000000000000000000",
+        "あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あいこくしん　あおぞら",
+        "a262d6fb6122ecf45be09c50492b31f92e9beb7d9a845987a02cefda57a15f9c467a17872029a9e92299b5cbdf306e3a0ee620245cbd508959b6cb7ca637bd55",
+    ),
+    (
+        "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+        "そつう　れきだい　ほんやく　わかす　りくつ　ばいか　ろせん　やちん　そつう　れきだい　ほんやく　わかめ",
+        "aee025cbe6ca256862f889e48110a6a382365142f7d16f2b9545285b3af64e542143a577e9c144e101a6bdca18f8d97ec3366ebf5b088b1c1af9bc31346e60d9",
+    ),
+    (
+        "80808080808080808080808080808080",
+        "そとづら　あまど　おおう　あこがれる　いくぶん　けいけん　あたえる　いよく　そとづら　あまど　おおう　あかちゃん",
+        "e51736736ebdf77eda23fa17e31475fa1d9509c78f1deb6b4aacfbd760a7e2ad769c714352c95143b5c1241985bcb407df36d64e75dd5a2b78ca5d2ba82a3544",
+    ),
+    (
+        "ffffffffffffffffffffffffffffffff",
+        "われる　われる　われる　われる　われる　われる　われる　われる　われる　われる　われる　ろんぶん",
+        "4cd2ef49b479af5e1efbbd1e0bdc117f6a29b1010211df4f78e2ed40082865793e57949236c43b9fe591ec70e5bb4298b8b71dc4b267bb96ed4ed282c8f7761c",
+    ),
+];
+
+fn zero_entropy_phrase(filler: &str, last: &str) -> String {
+    let mut words = vec![filler; 11];
+    words.push(last);
+    words.join(" ")
+}
+
+#[test]
+fn test_japanese_vectors() {
+    for (entropy, phrase, seed) in JAPANESE_VECTORS {
+        let mnemonic = Mnemonic::from_string(phrase.to_string()).unwrap();
+        assert_eq!(mnemonic.language(), Language::Japanese);
+        assert_eq!(mnemonic.entropy(), Vec::<u8>::from_hex(entropy).unwrap());
+
+        let seed = Vec::<u8>::from_hex(seed).unwrap();
+        let expected = ExtendedPrivKey::new_master(Network::Bitcoin, &seed).unwrap();
+        let master = DescriptorSecretKey::new(
+            Network::Bitcoin,
+            &mnemonic,
+            Some(JAPANESE_PASSPHRASE.to_string()),
+        )
+        .unwrap();
+        assert_eq!(master.as_string(), format!("{}/*", expected));
+    }
+}
+
+#[test]
+fn test_zero_entropy_in_each_language() {
+    for (language, filler, last) in [
+        (Language::English, "abandon", "about"),
+        (Language::Spanish, "ábaco", "abierto"),
+        (Language::French, "abaisser", "abeille"),
+        (Language::Italian, "abaco", "abete"),
+        (Language::Czech, "abdikace", "agrese"),
+        (Language::Korean, "가격", "가능"),
+        (Language::SimplifiedChinese, "的", "在"),
+        (Language::TraditionalChinese, "的", "在"),
+    ] {
+        let phrase = zero_entropy_phrase(filler, last);
+        let mnemonic = Mnemonic::from_string_in(language, phrase).unwrap();
+        assert_eq!(mnemonic.language(), language);
+        assert_eq!(mnemonic.entropy(), vec![0u8; 16]);
+    }
+}
+
+#[test]
+fn test_language_detection() {
+    let spanish = Mnemonic::from_string(zero_entropy_phrase("ábaco", "abierto")).unwrap();
+    assert_eq!(spanish.language(), Language::Spanish);
+
+    let korean = Mnemonic::from_string(zero_entropy_phrase("가격", "가능")).unwrap();
+    assert_eq!(korean.language(), Language::Korean);
+
+    // Both Chinese wordlists contain every word of this phrase.
+    assert!(matches!(
+        Mnemonic::from_string(zero_entropy_phrase("的", "在")),
+        Err(Error::InvalidMnemonic(bip39::Error::AmbiguousLanguages(_)))
+    ));
+
+    // A known word in the wrong language is still an unknown word.
+    assert!(matches!(
+        Mnemonic::from_string_in(Language::English, zero_entropy_phrase("ábaco", "abierto")),
+        Err(Error::InvalidMnemonic(bip39::Error::UnknownWord(0)))
+    ));
+}
+
+#[test]
+fn test_generate_in_each_language() {
+    for &language in Language::all() {
+        let mnemonic = Mnemonic::new_in(language, WordCount::Words24);
+        assert_eq!(mnemonic.language(), language);
+
+        let parsed = Mnemonic::from_string_in(language, mnemonic.as_string()).unwrap();
+        assert_eq!(parsed.entropy(), mnemonic.entropy());
+    }
+}
+
+#[test]
+fn test_nfkd_normalization() {
+    // "ábaco" with a combining acute accent instead of the precomposed character.
+    let decomposed = zero_entropy_phrase("a\u{301}baco", "abierto");
+    let mnemonic = Mnemonic::from_string(decomposed).unwrap();
+    assert_eq!(mnemonic.language(), Language::Spanish);
+    assert_eq!(mnemonic.entropy(), vec![0u8; 16]);
+
+    // "ガ" precomposed and as "カ" followed by a combining voiced sound mark.
+    let mnemonic = Mnemonic::from_string(JAPANESE_VECTORS[0].1.to_string()).unwrap();
+    let composed =
+        DescriptorSecretKey::new(Network::Bitcoin, &mnemonic, Some("\u{30ac}".to_string()))
+            .unwrap();
+    let decomposed = DescriptorSecretKey::new(
+        Network::Bitcoin,
+        &mnemonic,
+        Some("\u{30ab}\u{3099}".to_string()),
+    )
+    .unwrap();
+    let other = DescriptorSecretKey::new(Network::Bitcoin, &mnemonic, Some("\u{30ab}".to_string()))
+        .unwrap();
+    assert_eq!(composed.as_string(), decomposed.as_string());
+    assert_ne!(composed.as_string(), other.as_string());
+}
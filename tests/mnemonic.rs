@@ -0,0 +1,70 @@
+mod common;
+
+use bdk::keys::bip39;
+use extended_keys_derive_rust::{DescriptorSecretKey, Error, Mnemonic, Network, WordCount};
+
+use common::MNEMONIC;
+
+#[test]
+fn test_generate_word_counts() {
+    for (word_count, words, entropy_len) in [
+        (WordCount::Words12, 12, 16),
+        (WordCount::Words15, 15, 20),
+        (WordCount::Words18, 18, 24),
+        (WordCount::Words21, 21, 28),
+        (WordCount::Words24, 24, 32),
+    ] {
+        let mnemonic = Mnemonic::new(word_count);
+        assert_eq!(mnemonic.word_count(), words);
+        assert_eq!(mnemonic.entropy().len(), entropy_len);
+
+        let parsed = Mnemonic::from_string(mnemonic.as_string()).unwrap();
+        assert_eq!(parsed.entropy(), mnemonic.entropy());
+    }
+}
+
+#[test]
+fn test_entropy() {
+    let mnemonic = Mnemonic::from_string(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+            .to_string(),
+    )
+    .unwrap();
+    assert_eq!(mnemonic.entropy(), vec![0u8; 16]);
+
+    let mnemonic =
+        Mnemonic::from_string("zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong".to_string())
+            .unwrap();
+    assert_eq!(mnemonic.entropy(), vec![0xffu8; 16]);
+}
+
+#[test]
+fn test_validation_errors() {
+    let unknown_word = MNEMONIC.replace("sponsor", "sponsr");
+    assert!(matches!(
+        Mnemonic::from_string(unknown_word),
+        Err(Error::InvalidMnemonic(bip39::Error::UnknownWord(4)))
+    ));
+
+    let bad_checksum = MNEMONIC.replace("detect", "abandon");
+    assert!(matches!(
+        Mnemonic::from_string(bad_checksum),
+        Err(Error::InvalidMnemonic(bip39::Error::InvalidChecksum))
+    ));
+
+    let bad_word_count = MNEMONIC.replace(" detect", "");
+    assert!(matches!(
+        Mnemonic::from_string(bad_word_count),
+        Err(Error::InvalidMnemonic(bip39::Error::BadWordCount(23)))
+    ));
+}
+
+#[test]
+fn test_mnemonic_into_descriptor_secret_key() {
+    let mnemonic = Mnemonic::from_string(MNEMONIC.to_string()).unwrap();
+    let master = DescriptorSecretKey::new(Network::Testnet, &mnemonic, None).unwrap();
+    assert_eq!(master.as_string(), "tprv8ZgxMBicQKsPdWuqM1t1CDRvQtQuBPyfL6GbhQwtxDKgUAVPbxmj71pRA8raTqLrec5LyTs5TqCxdABcZr77bt2KyWA5bizJHnC4g4ysm4h/*");
+
+    let generated = Mnemonic::new(WordCount::Words12);
+    assert!(DescriptorSecretKey::new(Network::Bitcoin, &generated, None).is_ok());
+}
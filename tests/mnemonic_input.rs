@@ -0,0 +1,95 @@
+mod common;
+
+use extended_keys_derive_rust::{
+    DescriptorSecretKey, Error, Language, Mnemonic, MnemonicInput, Network, WordInput,
+};
+
+use common::MNEMONIC;
+
+#[test]
+fn test_four_letter_prefixes() {
+    let prefixes = MNEMONIC
+        .split(' ')
+        .map(|word| word.chars().take(4).collect::<String>())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let input = MnemonicInput::new(Language::English, prefixes);
+    assert!(input.ambiguous_positions().is_empty());
+    assert_eq!(
+        input.words()[0],
+        WordInput::Expanded {
+            input: "chao".to_string(),
+            word: "chaos".to_string()
+        }
+    );
+    assert_eq!(input.words()[5], WordInput::Exact("all".to_string()));
+
+    let mnemonic = input.to_mnemonic().unwrap();
+    assert_eq!(mnemonic.as_string(), MNEMONIC);
+    let master = DescriptorSecretKey::new(Network::Testnet, &mnemonic, None).unwrap();
+    assert_eq!(master.as_string(), "tprv8ZgxMBicQKsPdWuqM1t1CDRvQtQuBPyfL6GbhQwtxDKgUAVPbxmj71pRA8raTqLrec5LyTs5TqCxdABcZr77bt2KyWA5bizJHnC4g4ysm4h/*");
+}
+
+#[test]
+fn test_case_and_whitespace() {
+    let messy = format!("  {}\n", MNEMONIC.to_uppercase().replace(' ', "\t "));
+    let input = MnemonicInput::new(Language::English, messy);
+    assert_eq!(input.to_mnemonic().unwrap().as_string(), MNEMONIC);
+}
+
+#[test]
+fn test_typo_suggestions() {
+    let input = MnemonicInput::new(Language::English, MNEMONIC.replace("sponsor", "sponsr"));
+    assert_eq!(input.ambiguous_positions(), vec![4]);
+    assert_eq!(
+        input.words()[4],
+        WordInput::Ambiguous {
+            input: "sponsr".to_string(),
+            suggestions: vec!["sponsor".to_string()]
+        }
+    );
+    assert!(matches!(
+        input.to_mnemonic(),
+        Err(Error::AmbiguousWords(positions)) if positions == vec![4]
+    ));
+
+    let input = MnemonicInput::new(Language::English, "qqqqqqqq".to_string());
+    assert_eq!(
+        input.words()[0],
+        WordInput::Ambiguous {
+            input: "qqqqqqqq".to_string(),
+            suggestions: Vec::new()
+        }
+    );
+}
+
+#[test]
+fn test_ambiguous_prefixes() {
+    let input = MnemonicInput::new(Language::English, MNEMONIC.replace("secret", "sec"));
+    assert_eq!(input.ambiguous_positions(), vec![20]);
+    match &input.words()[20] {
+        WordInput::Ambiguous { input, suggestions } => {
+            assert_eq!(input, "sec");
+            assert!(suggestions.contains(&"secret".to_string()));
+            assert!(suggestions.contains(&"section".to_string()));
+            assert!(suggestions.iter().all(|word| word.starts_with("sec")));
+        }
+        other => panic!("unexpected match {:?}", other),
+    }
+}
+
+#[test]
+fn test_other_languages() {
+    // Precomposed input is normalized to the NFKD form the wordlists use.
+    let phrase = ["ábac"; 11].join(" ") + " abie";
+    let input = MnemonicInput::new(Language::Spanish, phrase);
+    assert!(input.ambiguous_positions().is_empty());
+    let mnemonic = input.to_mnemonic().unwrap();
+    assert_eq!(mnemonic.entropy(), vec![0u8; 16]);
+    assert_eq!(
+        mnemonic.entropy(),
+        Mnemonic::from_string(mnemonic.as_string())
+            .unwrap()
+            .entropy()
+    );
+}
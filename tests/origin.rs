@@ -0,0 +1,87 @@
+mod common;
+
+use extended_keys_derive_rust::{DescriptorKey, DescriptorSecretKey, Network};
+
+use common::{mnemonic, path};
+
+#[test]
+fn test_secret_key_multi_step_derivation() {
+    let master = DescriptorSecretKey::new(Network::Testnet, &mnemonic(), None).unwrap();
+    let one_step = master.derive(&path("m/84h/1h/0h")).unwrap();
+    let two_steps = master
+        .derive(&path("m/84h"))
+        .unwrap()
+        .derive(&path("m/1h/0h"))
+        .unwrap();
+    let three_steps = master
+        .derive(&path("m/84h"))
+        .unwrap()
+        .derive(&path("m/1h"))
+        .unwrap()
+        .derive(&path("m/0h"))
+        .unwrap();
+    assert!(one_step.as_string().starts_with("[d1d04177/84'/1'/0']tprv"));
+    assert_eq!(two_steps.as_string(), one_step.as_string());
+    assert_eq!(three_steps.as_string(), one_step.as_string());
+}
+
+#[test]
+fn test_public_key_multi_step_derivation() {
+    let account = DescriptorSecretKey::new(Network::Testnet, &mnemonic(), None)
+        .unwrap()
+        .derive(&path("m/84h/1h/0h"))
+        .unwrap();
+    let one_step = account.derive(&path("m/0/5")).unwrap().as_public().unwrap();
+    let two_steps = account
+        .as_public()
+        .unwrap()
+        .derive(&path("m/0"))
+        .unwrap()
+        .derive(&path("m/5"))
+        .unwrap();
+    assert!(one_step
+        .as_string()
+        .starts_with("[d1d04177/84'/1'/0'/0/5]tpub"));
+    assert_eq!(two_steps.as_string(), one_step.as_string());
+}
+
+#[test]
+fn test_descriptor_key_multi_step_derivation() {
+    let master = DescriptorKey::new(Network::Testnet, &mnemonic(), None).unwrap();
+    let one_step = master.derive(Some(&path("m/84h/1h/0h")), None).unwrap();
+    let two_steps = master
+        .derive(Some(&path("m/84h")), None)
+        .unwrap()
+        .derive(Some(&path("m/1h/0h")), None)
+        .unwrap();
+    assert!(one_step.as_string().starts_with("[d1d04177/84'/1'/0']tprv"));
+    assert_eq!(two_steps.as_string(), one_step.as_string());
+
+    let public_two_steps = one_step
+        .as_public()
+        .unwrap()
+        .derive(Some(&path("m/0")), None)
+        .unwrap()
+        .derive(Some(&path("m/5")), None)
+        .unwrap();
+    assert!(public_two_steps
+        .as_string()
+        .starts_with("[d1d04177/84'/1'/0'/0/5]tpub"));
+}
+
+#[test]
+fn test_implementations_agree() {
+    let secret = DescriptorSecretKey::new(Network::Testnet, &mnemonic(), None)
+        .unwrap()
+        .derive(&path("m/84h"))
+        .unwrap()
+        .derive(&path("m/1h/0h"))
+        .unwrap();
+    let descriptor_key = DescriptorKey::new(Network::Testnet, &mnemonic(), None)
+        .unwrap()
+        .derive(Some(&path("m/84h")), None)
+        .unwrap()
+        .derive(Some(&path("m/1h/0h")), None)
+        .unwrap();
+    assert_eq!(descriptor_key.as_string(), secret.as_string());
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use extended_keys_derive_rust::{
+    DescriptorSecretKey, Error, Network, PassphrasePattern, PassphraseRecovery, PassphraseSearch,
+    PassphraseTarget,
+};
+
+use common::{mnemonic, path};
+
+const PASSPHRASE: &str = "Satoshi42";
+// satoshi, SATOSHI, then Satoshi with no digit, ten single digits and then 42.
+const PASSPHRASE_INDEX: u64 = 2 * 111 + 1 + 10 + 42;
+
+fn pattern() -> PassphrasePattern {
+    PassphrasePattern::new()
+        .with_casing_variants(vec!["satoshi".to_string()])
+        .with_digits(2)
+}
+
+// The account key as `[fingerprint/84'/1'/0']tpub.../*` for the mnemonic and PASSPHRASE.
+fn account() -> String {
+    DescriptorSecretKey::new(Network::Testnet, &mnemonic(), Some(PASSPHRASE.to_string()))
+        .unwrap()
+        .derive(&path("m/84h/1h/0h"))
+        .unwrap()
+        .as_public()
+        .unwrap()
+        .as_string()
+}
+
+#[test]
+fn test_pattern_candidates() {
+    let pattern = pattern();
+    // satoshi, SATOSHI and Satoshi, each with up to two digits.
+    assert_eq!(pattern.candidate_count().unwrap(), 3 * 111);
+    assert_eq!(pattern.candidate(0), Some("satoshi".to_string()));
+    assert_eq!(pattern.candidate(1), Some("satoshi0".to_string()));
+    assert_eq!(pattern.candidate(11), Some("satoshi00".to_string()));
+    assert_eq!(pattern.candidate(111), Some("SATOSHI".to_string()));
+    assert_eq!(
+        pattern.candidate(PASSPHRASE_INDEX),
+        Some(PASSPHRASE.to_string())
+    );
+    assert_eq!(pattern.candidate(3 * 111), None);
+
+    let words = PassphrasePattern::new()
+        .with_casing_variants(vec!["Hal".to_string(), "hal".to_string()])
+        .with_words(vec!["".to_string(), "!".to_string(), "!".to_string()]);
+    // Hal, hal and HAL once each, followed by nothing or "!".
+    assert_eq!(words.candidate_count().unwrap(), 6);
+
+    assert_eq!(PassphrasePattern::new().candidate_count().unwrap(), 1);
+    assert_eq!(PassphrasePattern::new().candidate(0), Some(String::new()));
+}
+
+#[test]
+fn test_pattern_bounds() {
+    // Digits are generated from the index, so large slots cost nothing until searched.
+    let digits = PassphrasePattern::new().with_digits(11);
+    assert_eq!(digits.candidate_count().unwrap(), 111_111_111_111);
+    assert_eq!(digits.candidate(10), Some("9".to_string()));
+    assert_eq!(digits.candidate(11), Some("00".to_string()));
+    assert_eq!(
+        digits.candidate(111_111_111_110),
+        Some("99999999999".to_string())
+    );
+    assert_eq!(digits.candidate(111_111_111_111), None);
+
+    // More than 2^40 candidates, more than fit in a u64 and more digits than fit in a u64.
+    for pattern in [
+        PassphrasePattern::new().with_digits(12),
+        PassphrasePattern::new().with_digits(10).with_digits(10),
+        PassphrasePattern::new().with_digits(20),
+        PassphrasePattern::new().with_digits(u32::MAX),
+    ] {
+        assert!(matches!(
+            pattern.candidate_count(),
+            Err(Error::InvalidPassphrasePattern(_))
+        ));
+        assert_eq!(pattern.candidate(0), None);
+        assert!(matches!(
+            PassphraseRecovery::new(
+                &mnemonic(),
+                PassphraseTarget::Fingerprint("d1d04177".to_string()),
+                pattern
+            ),
+            Err(Error::InvalidPassphrasePattern(_))
+        ));
+    }
+}
+
+#[test]
+fn test_recover_from_fingerprint() {
+    let fingerprint = account()[1..9].to_string();
+    let recovery = PassphraseRecovery::new(
+        &mnemonic(),
+        PassphraseTarget::Fingerprint(fingerprint),
+        pattern(),
+    )
+    .unwrap();
+
+    let mut reports = Vec::new();
+    let search = recovery.recover(0, |progress| {
+        reports.push(progress);
+        true
+    });
+    assert_eq!(
+        search,
+        PassphraseSearch::Found {
+            passphrase: PASSPHRASE.to_string(),
+            index: PASSPHRASE_INDEX,
+        }
+    );
+    assert!(reports.windows(2).all(|w| w[0].checked < w[1].checked));
+    assert!(reports.iter().all(|progress| progress.total == 3 * 111));
+}
+
+#[test]
+fn test_recover_from_xpub() {
+    let account = account();
+    let xpub = account[account.find(']').unwrap() + 1..account.len() - 2].to_string();
+    let recovery = PassphraseRecovery::new(
+        &mnemonic(),
+        PassphraseTarget::Xpub {
+            xpub,
+            path: path("m/84h/1h/0h"),
+        },
+        PassphrasePattern::new()
+            .with_words(vec!["Satoshi".to_string(), "Hal".to_string()])
+            .with_words(vec!["41".to_string(), "42".to_string()]),
+    )
+    .unwrap();
+    assert_eq!(
+        recovery.recover(0, |_| true),
+        PassphraseSearch::Found {
+            passphrase: PASSPHRASE.to_string(),
+            index: 1,
+        }
+    );
+}
+
+#[test]
+fn test_stop_and_resume() {
+    let fingerprint = account()[1..9].to_string();
+    // Large enough that the first batch cannot reach the match on any machine.
+    let recovery = PassphraseRecovery::new(
+        &mnemonic(),
+        PassphraseTarget::Fingerprint(fingerprint.clone()),
+        PassphrasePattern::new()
+            .with_casing_variants(vec!["satoshi".to_string()])
+            .with_digits(4),
+    )
+    .unwrap();
+    let index = 2 * 11111 + 1 + 10 + 42;
+
+    let search = recovery.recover(0, |_| false);
+    let resume_from = match search {
+        PassphraseSearch::Stopped { resume_from } => resume_from,
+        other => panic!("unexpected search result {:?}", other),
+    };
+    assert!(resume_from > 0 && resume_from <= index);
+
+    assert_eq!(
+        recovery.recover(index - 3, |_| true),
+        PassphraseSearch::Found {
+            passphrase: PASSPHRASE.to_string(),
+            index,
+        }
+    );
+
+    // Resuming past the match exhausts the remaining candidates.
+    let recovery = PassphraseRecovery::new(
+        &mnemonic(),
+        PassphraseTarget::Fingerprint(fingerprint),
+        pattern(),
+    )
+    .unwrap();
+    assert_eq!(
+        recovery.recover(PASSPHRASE_INDEX + 1, |_| true),
+        PassphraseSearch::Exhausted
+    );
+}
+
+#[test]
+fn test_invalid_targets() {
+    assert!(matches!(
+        PassphraseRecovery::new(
+            &mnemonic(),
+            PassphraseTarget::Fingerprint("not hex".to_string()),
+            pattern()
+        ),
+        Err(Error::InvalidFingerprint(_))
+    ));
+    assert!(matches!(
+        PassphraseRecovery::new(
+            &mnemonic(),
+            PassphraseTarget::Xpub {
+                xpub: "tpubnothing".to_string(),
+                path: path("m/0"),
+            },
+            pattern()
+        ),
+        Err(Error::InvalidKey(_))
+    ));
+}
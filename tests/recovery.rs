@@ -0,0 +1,134 @@
+mod common;
+
+use extended_keys_derive_rust::{
+    DerivationPath, DescriptorSecretKey, Error, Language, Mnemonic, MnemonicRecovery, Network,
+};
+
+use common::MNEMONIC;
+
+fn recover(template: String, fingerprint: Option<&str>) -> Vec<String> {
+    MnemonicRecovery::new(
+        Language::English,
+        template,
+        fingerprint.map(str::to_string),
+        None,
+    )
+    .unwrap()
+    .recover()
+    .iter()
+    .map(|mnemonic| mnemonic.as_string())
+    .collect()
+}
+
+#[test]
+fn test_missing_checksum_word() {
+    let template = MNEMONIC.replace("detect", "?");
+    let candidates = recover(template.clone(), None);
+    // The last of 24 words carries 3 bits of entropy next to the 8 bit checksum.
+    assert_eq!(candidates.len(), 8);
+    assert!(candidates.contains(&MNEMONIC.to_string()));
+
+    assert_eq!(
+        recover(template, Some("d1d04177")),
+        vec![MNEMONIC.to_string()]
+    );
+}
+
+#[test]
+fn test_illegible_word_with_fingerprint() {
+    let template = MNEMONIC.replace("sponsor", "?");
+    assert_eq!(
+        recover(template, Some("d1d04177")),
+        vec![MNEMONIC.to_string()]
+    );
+}
+
+#[test]
+fn test_fingerprint_matches_descriptor_origin() {
+    let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    let master = DescriptorSecretKey::new(
+        Network::Bitcoin,
+        &Mnemonic::from_string(mnemonic.to_string()).unwrap(),
+        None,
+    )
+    .unwrap();
+    let account = master
+        .derive(&DerivationPath::new("m/0".to_string()).unwrap())
+        .unwrap()
+        .as_string();
+    let fingerprint = &account[1..9];
+
+    let candidates = recover(mnemonic.replace("about", "?"), None);
+    assert_eq!(candidates.len(), 128);
+    assert_eq!(
+        recover(mnemonic.replace("about", "?"), Some(fingerprint)),
+        vec![mnemonic.to_string()]
+    );
+}
+
+#[test]
+fn test_omitted_word_at_unknown_position() {
+    let template = MNEMONIC.replace("wisdom ", "");
+    let recovery = MnemonicRecovery::new(
+        Language::English,
+        template,
+        Some("d1d04177".to_string()),
+        None,
+    )
+    .unwrap();
+    assert_eq!(recovery.candidate_count(), 24 * 2048);
+    let found = recovery.recover();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].as_string(), MNEMONIC);
+}
+
+#[test]
+fn test_results_do_not_depend_on_threads() {
+    let template = MNEMONIC.replace("cable", "?").replace("crack", "crac");
+    let recovery = MnemonicRecovery::new(Language::English, template, None, None).unwrap();
+    let single: Vec<String> = recovery
+        .recover_with_threads(1)
+        .iter()
+        .map(|mnemonic| mnemonic.as_string())
+        .collect();
+    let parallel: Vec<String> = recovery
+        .recover_with_threads(5)
+        .iter()
+        .map(|mnemonic| mnemonic.as_string())
+        .collect();
+    assert_eq!(single, parallel);
+    assert!(single.contains(&MNEMONIC.to_string()));
+}
+
+#[test]
+fn test_invalid_templates() {
+    let new = |template: String, fingerprint: Option<&str>| {
+        MnemonicRecovery::new(
+            Language::English,
+            template,
+            fingerprint.map(str::to_string),
+            None,
+        )
+    };
+
+    assert!(matches!(
+        new(MNEMONIC.to_string(), None),
+        Err(Error::InvalidRecoveryTemplate(_))
+    ));
+    assert!(matches!(
+        new(MNEMONIC.replace("time speed sponsor", "? ? ?"), None),
+        Err(Error::InvalidRecoveryTemplate(_))
+    ));
+    assert!(matches!(
+        new(MNEMONIC.replace("time speed ", ""), None),
+        Err(Error::InvalidRecoveryTemplate(_))
+    ));
+    assert!(matches!(
+        new(MNEMONIC.replace("detect", "?"), Some("d1d0417")),
+        Err(Error::InvalidFingerprint(_))
+    ));
+    assert!(matches!(
+        new(MNEMONIC.replace("detect", "?").replace("sponsor", "sponsr"), None),
+        Err(Error::AmbiguousWords(positions)) if positions == vec![4]
+    ));
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use extended_keys_derive_rust::{
+    DerivationPath, DescriptorKey, Error, Legacy, Network, ScriptContextEnum, Segwitv0, Tap,
+};
+
+use common::mnemonic;
+
+// The generator point, which is the public key of the secret key 1, in each encoding.
+const UNCOMPRESSED: &str = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+const COMPRESSED: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+const X_ONLY: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+const UNCOMPRESSED_WIF: &str = "5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf";
+const COMPRESSED_WIF: &str = "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn";
+
+fn key(key: &str) -> DescriptorKey {
+    DescriptorKey::from_string(Network::Bitcoin, key.to_string()).unwrap()
+}
+
+#[test]
+fn test_extended_keys_convert_to_every_context() {
+    let mnemonic = mnemonic();
+    let master = DescriptorKey::new(Network::Testnet, &mnemonic, None).unwrap();
+    assert_eq!(master.script_context(), ScriptContextEnum::Legacy);
+
+    let segwit = master.to_context::<Segwitv0>().unwrap();
+    assert_eq!(segwit.script_context(), ScriptContextEnum::Segwitv0);
+    assert_eq!(segwit.as_string(), master.as_string());
+
+    // Operations keep the context.
+    let path = DerivationPath::new("m/86h/1h/0h".to_string()).unwrap();
+    let taproot = master
+        .to_context::<Tap>()
+        .unwrap()
+        .derive(Some(&path), None)
+        .unwrap()
+        .as_public()
+        .unwrap();
+    assert_eq!(taproot.script_context(), ScriptContextEnum::Tap);
+    assert_eq!(
+        taproot.to_context::<Legacy>().unwrap(),
+        master
+            .derive(Some(&path), None)
+            .unwrap()
+            .as_public()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_uncompressed_keys_are_legacy_only() {
+    for uncompressed in [UNCOMPRESSED, UNCOMPRESSED_WIF] {
+        let legacy = key(uncompressed);
+        assert!(matches!(
+            legacy.to_context::<Segwitv0>(),
+            Err(Error::InvalidScriptContext(_))
+        ));
+        assert!(matches!(
+            legacy.to_context::<Tap>(),
+            Err(Error::InvalidScriptContext(_))
+        ));
+        assert!(matches!(
+            DescriptorKey::<Segwitv0>::from_string_in_context(
+                Network::Bitcoin,
+                uncompressed.to_string()
+            ),
+            Err(Error::InvalidScriptContext(_))
+        ));
+    }
+    assert_eq!(
+        key(UNCOMPRESSED_WIF).as_public().unwrap().as_string(),
+        UNCOMPRESSED
+    );
+
+    for compressed in [COMPRESSED, COMPRESSED_WIF] {
+        let legacy = key(compressed);
+        assert!(legacy.to_context::<Segwitv0>().is_ok());
+        assert!(legacy.to_context::<Tap>().is_ok());
+    }
+}
+
+#[test]
+fn test_x_only_keys_are_taproot_only() {
+    let taproot =
+        DescriptorKey::<Tap>::from_string_in_context(Network::Bitcoin, X_ONLY.to_string()).unwrap();
+    assert_eq!(taproot.as_string(), X_ONLY);
+    assert!(matches!(
+        taproot.to_context::<Segwitv0>(),
+        Err(Error::InvalidScriptContext(_))
+    ));
+    let error = DescriptorKey::from_string(Network::Bitcoin, X_ONLY.to_string()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid key for script context: x-only keys are not allowed in legacy scripts"
+    );
+}
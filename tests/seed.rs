@@ -0,0 +1,121 @@
+mod common;
+
+use bdk::bitcoin::hashes::hex::{FromHex, ToHex};
+use bdk::keys::bip39;
+use extended_keys_derive_rust::{
+    DescriptorKey, DescriptorPublicKey, DescriptorSecretKey, Error, Mnemonic, Network,
+};
+
+use common::{mnemonic, path, TREZOR_SEED, TREZOR_XPRV};
+
+#[test]
+fn test_seed_and_entropy() {
+    let mnemonic = Mnemonic::from_entropy(vec![0u8; 16]).unwrap();
+    assert_eq!(
+        mnemonic.as_string(),
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+    );
+    assert_eq!(
+        mnemonic.seed(Some("TREZOR".to_string())).to_hex(),
+        TREZOR_SEED
+    );
+    assert_eq!(mnemonic.seed(None), mnemonic.seed(Some(String::new())));
+
+    assert!(matches!(
+        Mnemonic::from_entropy(vec![0u8; 17]),
+        Err(Error::InvalidMnemonic(bip39::Error::BadEntropyBitCount(
+            136
+        )))
+    ));
+}
+
+#[test]
+fn test_master_key_constructors() {
+    let expected = format!("{}/*", TREZOR_XPRV);
+    let seed = Vec::<u8>::from_hex(TREZOR_SEED).unwrap();
+
+    let from_seed = DescriptorSecretKey::from_seed(Network::Bitcoin, seed.clone()).unwrap();
+    assert_eq!(from_seed.as_string(), expected);
+
+    let from_entropy =
+        DescriptorSecretKey::from_entropy(Network::Bitcoin, vec![0u8; 16], Some("TREZOR".into()))
+            .unwrap();
+    assert_eq!(from_entropy.as_string(), expected);
+
+    let from_mnemonic = DescriptorSecretKey::new(
+        Network::Bitcoin,
+        &Mnemonic::from_entropy(vec![0u8; 16]).unwrap(),
+        Some("TREZOR".to_string()),
+    )
+    .unwrap();
+    assert_eq!(from_mnemonic.as_string(), expected);
+
+    let descriptor_key = DescriptorKey::from_seed(Network::Bitcoin, seed).unwrap();
+    assert_eq!(descriptor_key.as_string(), expected);
+    let descriptor_key =
+        DescriptorKey::from_entropy(Network::Bitcoin, vec![0u8; 16], Some("TREZOR".into()))
+            .unwrap();
+    assert_eq!(descriptor_key.as_string(), expected);
+}
+
+#[test]
+fn test_seed_lengths() {
+    assert!(DescriptorSecretKey::from_seed(Network::Testnet, vec![1u8; 16]).is_ok());
+    for len in [0, 15, 65] {
+        assert!(matches!(
+            DescriptorSecretKey::from_seed(Network::Testnet, vec![1u8; len]),
+            Err(Error::InvalidKey(_))
+        ));
+        assert!(matches!(
+            DescriptorKey::from_seed(Network::Testnet, vec![1u8; len]),
+            Err(Error::InvalidKey(_))
+        ));
+    }
+}
+
+#[test]
+fn test_master_fingerprint() {
+    let mnemonic = mnemonic();
+    assert_eq!(mnemonic.master_fingerprint(None), "d1d04177");
+    assert_ne!(
+        mnemonic.master_fingerprint(Some("TREZOR".to_string())),
+        "d1d04177"
+    );
+
+    let master = DescriptorSecretKey::new(Network::Testnet, &mnemonic, None).unwrap();
+    assert_eq!(master.master_fingerprint(), Some("d1d04177".to_string()));
+    let account = master.derive(&path("m/84h/1h/0h")).unwrap();
+    assert_eq!(account.master_fingerprint(), Some("d1d04177".to_string()));
+    assert_eq!(
+        account.as_public().unwrap().master_fingerprint(),
+        Some("d1d04177".to_string())
+    );
+
+    let descriptor_key = DescriptorKey::new(Network::Testnet, &mnemonic, None).unwrap();
+    assert_eq!(
+        descriptor_key.master_fingerprint(),
+        Some("d1d04177".to_string())
+    );
+    assert_eq!(
+        descriptor_key
+            .derive(Some(&path("m/0")), None)
+            .unwrap()
+            .as_public()
+            .unwrap()
+            .master_fingerprint(),
+        Some("d1d04177".to_string())
+    );
+
+    // Without origin only a master key knows its own fingerprint.
+    let account = account.as_public().unwrap().as_string();
+    let bare = &account[account.find(']').unwrap() + 1..];
+    let bare = DescriptorPublicKey::from_string(Network::Testnet, bare.to_string()).unwrap();
+    assert_eq!(bare.master_fingerprint(), None);
+
+    let wif = DescriptorSecretKey::from_string(
+        Network::Testnet,
+        "cMpMxK92W1DjqDvWV3pMn4xLwAuQJhNF3MFqkEHUQRPQofUJku8R".to_string(),
+    )
+    .unwrap();
+    assert_eq!(wif.master_fingerprint(), None);
+}
@@ -0,0 +1,144 @@
+use bdk::bitcoin::hashes::hex::FromHex;
+use extended_keys_derive_rust::{
+    DerivationPath, DescriptorSecretKey, Error, ImportedSeed, Network, ScriptType, SeedFormat,
+};
+
+// An aezeed of the entropy and birthday of LND's test vectors, enciphered with the default
+// passphrase and with a custom one.
+const AEZEED: &str = "absorb original enlist once climb erode kid thrive kitchen giant define tube orange leader harbor comfort olive fatal success suggest drink penalty chimney ritual";
+const AEZEED_WITH_PASSPHRASE: &str = "absorb century submit father path glove gloom super divert garden ice mirror wisdom grass dice kit ugly castle success suggest drink monster congress flight";
+const AEZEED_PASSPHRASE: &str = "!very_safe_55345_password*";
+const AEZEED_ENTROPY: &str = "81b637d86359e6960de795e41e0b4cfd";
+
+#[test]
+fn test_electrum_standard() {
+    let seed = ImportedSeed::from_electrum(
+        Network::Bitcoin,
+        "cycle rocket west magnet parrot shuffle foot correct salt library feed song".to_string(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(seed.format(), SeedFormat::ElectrumStandard);
+    assert_eq!(seed.script_type(), ScriptType::P2pkh);
+    assert_eq!(seed.birthday(), None);
+    assert_eq!(
+        seed.master_key().as_string(),
+        "xprv9s21ZrQH143K32jECVM729vWgGq4mUDJCk1ozqAStTphzQtCTuoFmFafNoG1g55iCnBTXUzz3zWnDb5CVLGiFvmaZjuazHDL8a81cPQ8KL6/*"
+    );
+    // Standard wallets derive their chains from the master key itself.
+    assert_eq!(
+        seed.account_key().unwrap().as_string(),
+        "[48adc7a0]xprv9s21ZrQH143K32jECVM729vWgGq4mUDJCk1ozqAStTphzQtCTuoFmFafNoG1g55iCnBTXUzz3zWnDb5CVLGiFvmaZjuazHDL8a81cPQ8KL6/*"
+    );
+}
+
+#[test]
+fn test_electrum_segwit() {
+    let seed = ImportedSeed::from_electrum(
+        Network::Bitcoin,
+        "bitter grass shiver impose acquire brush forget axis eager alone wine silver".to_string(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(seed.format(), SeedFormat::ElectrumSegwit);
+    assert_eq!(seed.script_type(), ScriptType::P2wpkh);
+    // Electrum shows this account key as a zprv.
+    assert_eq!(
+        seed.account_key().unwrap().as_string(),
+        "[b2e35a7d/0']xprv9vDQcb2p1HccSFAu1Q5Qws4XCZ1SgzVsPvR4Y3jSUajszrY6bmGudRwHUyT619UrPQyndCc5CsN4xwiAKtBG3wx2Gvfu9MmCEHLvrGMnDMr/*"
+    );
+
+    // Case and whitespace are normalized away, but the seed extension changes the key.
+    let phrase =
+        "wild father tree among universe such mobile favorite target dynamic credit identify";
+    let plain = ImportedSeed::from_electrum(Network::Bitcoin, phrase.to_string(), None).unwrap();
+    let shouted = ImportedSeed::from_electrum(
+        Network::Bitcoin,
+        format!("  {}  ", phrase.to_uppercase().replace(' ', "   ")),
+        Some(String::new()),
+    )
+    .unwrap();
+    let extended = ImportedSeed::from_electrum(
+        Network::Bitcoin,
+        phrase.to_string(),
+        Some("Did you ever hear the tragedy of Darth Plagueis the Wise?".to_string()),
+    )
+    .unwrap();
+    assert_eq!(
+        plain.master_key().as_string(),
+        shouted.master_key().as_string()
+    );
+    assert_ne!(
+        plain.master_key().as_string(),
+        extended.master_key().as_string()
+    );
+    assert_eq!(extended.format(), SeedFormat::ElectrumSegwit);
+}
+
+#[test]
+fn test_electrum_rejects_other_seeds() {
+    // A valid BIP39 mnemonic is not an Electrum seed.
+    assert!(matches!(
+        ImportedSeed::from_electrum(
+            Network::Bitcoin,
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string(),
+            None,
+        ),
+        Err(Error::InvalidSeedPhrase(_))
+    ));
+}
+
+#[test]
+fn test_aezeed() {
+    let expected =
+        DescriptorSecretKey::from_seed(Network::Bitcoin, Vec::from_hex(AEZEED_ENTROPY).unwrap())
+            .unwrap();
+
+    let seed = ImportedSeed::from_aezeed(Network::Bitcoin, AEZEED.to_string(), None).unwrap();
+    assert_eq!(seed.format(), SeedFormat::Aezeed);
+    assert_eq!(seed.script_type(), ScriptType::P2wpkh);
+    assert_eq!(seed.master_key().as_string(), expected.as_string());
+    let account = expected
+        .derive(&DerivationPath::new("m/84h/0h/0h".to_string()).unwrap())
+        .unwrap();
+    assert_eq!(seed.account_key().unwrap().as_string(), account.as_string());
+    // 3365 days after the genesis block.
+    assert_eq!(seed.birthday(), Some(1521742505));
+
+    let seed = ImportedSeed::from_aezeed(
+        Network::Testnet,
+        AEZEED_WITH_PASSPHRASE.to_string(),
+        Some(AEZEED_PASSPHRASE.to_string()),
+    )
+    .unwrap();
+    assert!(seed
+        .account_key()
+        .unwrap()
+        .as_string()
+        .contains("/84'/1'/0']tprv"));
+    assert_eq!(
+        seed.master_key().master_fingerprint(),
+        expected.master_fingerprint()
+    );
+
+    // Without its passphrase the second seed does not decrypt.
+    assert!(matches!(
+        ImportedSeed::from_aezeed(Network::Bitcoin, AEZEED_WITH_PASSPHRASE.to_string(), None),
+        Err(Error::InvalidSeedPhrase(_))
+    ));
+}
+
+#[test]
+fn test_aezeed_malformed() {
+    let words: Vec<&str> = AEZEED.split(' ').collect();
+    for phrase in [
+        words[..23].join(" "),
+        format!("{} bitcoin", words[..23].join(" ")),
+        format!("{} {}", words[..23].join(" "), words[0]),
+    ] {
+        assert!(matches!(
+            ImportedSeed::from_aezeed(Network::Bitcoin, phrase, None),
+            Err(Error::InvalidSeedPhrase(_))
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+use extended_keys_derive_rust::{Error, Language, Mnemonic, SeedQr, SeedQrFormat};
+
+// The examples of the SeedQR specification.
+const WORDS_12: &str = "forum undo fragile fade shy sign arrest garment culture tube off merit";
+const DIGITS_12: &str = "073318950739065415961602009907670428187212261116";
+const WORDS_24: &str = "attack pizza motion avocado network gather crop fresh patrol unusual wild holiday candy pony ranch winter theme error hybrid van cereal salon goddess expire";
+const DIGITS_24: &str = "011513251154012711900771041507421289190620080870026613431420201617920614089619290300152408010643";
+
+fn mnemonic(words: &str) -> Mnemonic {
+    Mnemonic::from_string(words.to_string()).unwrap()
+}
+
+#[test]
+fn test_standard() {
+    for (words, digits) in [(WORDS_12, DIGITS_12), (WORDS_24, DIGITS_24)] {
+        let seed_qr = SeedQr::new(&mnemonic(words), SeedQrFormat::Standard).unwrap();
+        assert_eq!(seed_qr.payload(), digits.as_bytes());
+
+        let decoded = SeedQr::from_payload(digits.as_bytes().to_vec()).unwrap();
+        assert_eq!(decoded.format(), SeedQrFormat::Standard);
+        assert_eq!(decoded.mnemonic().as_string(), words);
+        assert_eq!(decoded, seed_qr);
+    }
+}
+
+#[test]
+fn test_compact() {
+    for words in [WORDS_12, WORDS_24] {
+        let seed_qr = SeedQr::new(&mnemonic(words), SeedQrFormat::Compact).unwrap();
+        assert_eq!(seed_qr.payload(), mnemonic(words).entropy());
+
+        let decoded = SeedQr::from_payload(seed_qr.payload()).unwrap();
+        assert_eq!(decoded.format(), SeedQrFormat::Compact);
+        assert_eq!(decoded.mnemonic().as_string(), words);
+    }
+}
+
+#[test]
+fn test_rendering() {
+    // Standard SeedQRs are 25 and 29 modules wide and Compact ones 21 and 25, plus a quiet
+    // zone of 4 modules on every side.
+    for (words, format, modules) in [
+        (WORDS_12, SeedQrFormat::Standard, 25),
+        (WORDS_24, SeedQrFormat::Standard, 29),
+        (WORDS_12, SeedQrFormat::Compact, 21),
+        (WORDS_24, SeedQrFormat::Compact, 25),
+    ] {
+        let seed_qr = SeedQr::new(&mnemonic(words), format).unwrap();
+        let size: usize = modules + 8;
+
+        let terminal = seed_qr.to_terminal();
+        assert_eq!(terminal.lines().count(), size.div_ceil(2));
+        assert!(terminal.lines().all(|line| line.chars().count() == size));
+
+        let svg = seed_qr.to_svg(10);
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains(&format!("width=\"{}\"", size * 10)));
+
+        let png = seed_qr.to_png(3);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[16..20], &(size as u32 * 3).to_be_bytes());
+    }
+}
+
+#[test]
+fn test_invalid() {
+    let spanish = Mnemonic::from_entropy_in(Language::Spanish, vec![0; 16]).unwrap();
+    let fifteen_words = Mnemonic::from_entropy(vec![0; 20]).unwrap();
+    for mnemonic in [spanish, fifteen_words] {
+        assert!(matches!(
+            SeedQr::new(&mnemonic, SeedQrFormat::Standard),
+            Err(Error::InvalidSeedQr(_))
+        ));
+    }
+
+    let mut out_of_range = DIGITS_12.to_string();
+    out_of_range.replace_range(..4, "2048");
+    let mut not_digits = DIGITS_12.to_string();
+    not_digits.replace_range(..4, "07a3");
+    for payload in [
+        DIGITS_12.as_bytes()[..44].to_vec(),
+        out_of_range.into_bytes(),
+        not_digits.into_bytes(),
+    ] {
+        assert!(matches!(
+            SeedQr::from_payload(payload),
+            Err(Error::InvalidSeedQr(_))
+        ));
+    }
+
+    // The last word carries the checksum.
+    let mut bad_checksum = DIGITS_12.to_string();
+    bad_checksum.replace_range(44.., "1117");
+    assert!(matches!(
+        SeedQr::from_payload(bad_checksum.into_bytes()),
+        Err(Error::InvalidMnemonic(_))
+    ));
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use extended_keys_derive_rust::{
+    DerivationPath, DescriptorSecretKey, Error, Mnemonic, Network, WordCount,
+};
+
+use common::MNEMONIC;
+
+// The three share example published with Coldcard's Seed XOR.
+const SHARES: [&str; 3] = [
+    "romance wink lottery autumn shop bring dawn tongue range crater truth ability miss spice fitness easy legal release recall obey exchange recycle dragon room",
+    "lion misery divide hurry latin fluid camp advance illegal lab pyramid unaware eager fringe sick camera series noodle toy crowd jeans select depth lounge",
+    "vault nominee cradle silk own frown throw leg cactus recall talent worry gadget surface shy planet purpose coffee drip few seven term squeeze educate",
+];
+const SECRET: &str = "silent toe meat possible chair blossom wait occur this worth option bag nurse find fish scene bench asthma bike wage world quit primary indoor";
+
+fn mnemonic(phrase: &str) -> Mnemonic {
+    Mnemonic::from_string(phrase.to_string()).unwrap()
+}
+
+#[test]
+fn test_coldcard_vector() {
+    let shares: Vec<_> = SHARES.iter().map(|share| mnemonic(share)).collect();
+    let secret = Mnemonic::seed_xor_combine(shares.clone(), None).unwrap();
+    assert_eq!(secret.as_string(), SECRET);
+
+    let reversed = shares.into_iter().rev().collect();
+    let fingerprint = secret.master_fingerprint(None);
+    let secret = Mnemonic::seed_xor_combine(reversed, Some(fingerprint.to_uppercase())).unwrap();
+    assert_eq!(secret.as_string(), SECRET);
+}
+
+#[test]
+fn test_split_and_combine_into_key() {
+    for shares in 2..=4 {
+        let split = mnemonic(MNEMONIC).seed_xor_split(shares).unwrap();
+        assert_eq!(split.len(), shares);
+        assert!(split.iter().all(|share| share.word_count() == 24));
+        assert!(split.iter().all(|share| share.as_string() != MNEMONIC));
+
+        let combined = Mnemonic::seed_xor_combine(split, Some("d1d04177".to_string())).unwrap();
+        let master = DescriptorSecretKey::new(Network::Testnet, &combined, None).unwrap();
+        let account = master
+            .derive(&DerivationPath::new("m/84h/1h/0h".to_string()).unwrap())
+            .unwrap();
+        assert!(account.as_string().starts_with("[d1d04177/84'/1'/0']tprv"));
+    }
+
+    let twelve = Mnemonic::new(WordCount::Words12);
+    let split = twelve.seed_xor_split(3).unwrap();
+    assert!(split.iter().all(|share| share.word_count() == 12));
+    assert_eq!(
+        Mnemonic::seed_xor_combine(split, None).unwrap().as_string(),
+        twelve.as_string()
+    );
+}
+
+#[test]
+fn test_fingerprint_mismatch() {
+    // Leaving out a share yields a different, still valid, phrase.
+    let shares = vec![mnemonic(SHARES[0]), mnemonic(SHARES[1])];
+    let expected = mnemonic(SECRET).master_fingerprint(None);
+    match Mnemonic::seed_xor_combine(shares, Some(expected.clone())) {
+        Err(Error::FingerprintMismatch {
+            expected: reported,
+            found,
+        }) => {
+            assert_eq!(reported, expected);
+            assert_ne!(found, expected);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn test_invalid_shares() {
+    assert!(matches!(
+        mnemonic(MNEMONIC).seed_xor_split(1),
+        Err(Error::InvalidShares(_))
+    ));
+    assert!(matches!(
+        Mnemonic::seed_xor_combine(vec![mnemonic(MNEMONIC)], None),
+        Err(Error::InvalidShares(_))
+    ));
+    let twelve = Mnemonic::new(WordCount::Words12);
+    assert!(matches!(
+        Mnemonic::seed_xor_combine(vec![mnemonic(MNEMONIC), twelve], None),
+        Err(Error::InvalidShares(_))
+    ));
+}
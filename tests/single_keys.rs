@@ -0,0 +1,66 @@
+mod common;
+
+use extended_keys_derive_rust::{
+    DescriptorKey, DescriptorPublicKey, DescriptorSecretKey, Error, Network,
+};
+
+use common::path;
+
+const WIF: &str = "[d1d04177/84'/1'/0'/0/0]cMpMxK92W1DjqDvWV3pMn4xLwAuQJhNF3MFqkEHUQRPQofUJku8R";
+const PUBKEY: &str =
+    "[d1d04177/84'/1'/0'/0/0]02989c0b76cb563971fdc9bef31ec06c3560f3249d6ee9e5d83c57625596e05f6f";
+
+#[test]
+fn test_single_secret_key_round_trip() {
+    let dsk = DescriptorSecretKey::from_string(Network::Testnet, WIF.to_string()).unwrap();
+    assert_eq!(dsk.as_string(), WIF);
+    assert_eq!(dsk.as_public().unwrap().as_string(), PUBKEY);
+}
+
+#[test]
+fn test_single_public_key_round_trip() {
+    let dpk = DescriptorPublicKey::from_string(Network::Bitcoin, PUBKEY.to_string()).unwrap();
+    assert_eq!(dpk.as_string(), PUBKEY);
+}
+
+#[test]
+fn test_single_keys_cannot_be_derived() {
+    let dsk = DescriptorSecretKey::from_string(Network::Testnet, WIF.to_string()).unwrap();
+    assert!(matches!(
+        dsk.derive(&path("m/0")),
+        Err(Error::SingleKeyDerivation)
+    ));
+    assert!(matches!(
+        dsk.extend(&path("m/0")),
+        Err(Error::SingleKeyDerivation)
+    ));
+
+    let dpk = dsk.as_public().unwrap();
+    assert!(matches!(
+        dpk.derive(&path("m/0")),
+        Err(Error::SingleKeyDerivation)
+    ));
+    assert!(matches!(
+        dpk.extend(&path("m/0")),
+        Err(Error::SingleKeyDerivation)
+    ));
+
+    let dk = DescriptorKey::try_from(&dsk).unwrap();
+    assert!(matches!(
+        dk.derive(Some(&path("m/0")), None),
+        Err(Error::SingleKeyDerivation)
+    ));
+    assert_eq!(dk.as_public().unwrap().as_string(), PUBKEY);
+}
+
+#[test]
+fn test_single_secret_key_network_mismatch() {
+    let result = DescriptorSecretKey::from_string(Network::Bitcoin, WIF.to_string());
+    assert!(matches!(
+        result,
+        Err(Error::NetworkMismatch {
+            expected: Network::Bitcoin,
+            found: Network::Testnet,
+        })
+    ));
+}
@@ -0,0 +1,208 @@
+mod common;
+
+use bdk::bitcoin::hashes::hex::{FromHex, ToHex};
+use extended_keys_derive_rust::{
+    DescriptorKey, DescriptorSecretKey, Error, Network, Slip39, Slip39Group,
+};
+
+use common::{TREZOR_SEED, TREZOR_XPRV};
+
+// The official SLIP-39 test vectors, all made with passphrase "TREZOR". An empty master
+// secret marks a set of mnemonics that must be rejected. The official file has 45 vectors;
+// #44 (extendable 2-of-3 sharing, 256 bits) and #45 (mnemonics that catch errors in the
+// modular arithmetic of the interpolation) are not included yet.
+const VECTORS: &str = include_str!("fixtures/slip39_vectors.json");
+
+// Shares made by `Slip39::split` in the same format, whose master secret an independent
+// implementation (the sssmc39 crate) recovers too. Interpolating 16 shares takes the sums of
+// logarithms far past 255, which the official vectors with at most 5 shares do not.
+const CROSS_CHECKED_VECTORS: &str = include_str!("fixtures/slip39_cross_checked.json");
+
+fn group(member_threshold: u8, member_count: u8) -> Slip39Group {
+    Slip39Group {
+        member_threshold,
+        member_count,
+    }
+}
+
+fn check_vectors(vectors: Vec<(String, Vec<String>, String)>) {
+    for (description, mnemonics, secret) in vectors {
+        let result = Slip39::combine(mnemonics.clone(), Some("TREZOR".to_string()));
+        if secret.is_empty() {
+            assert!(
+                matches!(result, Err(Error::InvalidShares(_))),
+                "{}",
+                description
+            );
+            continue;
+        }
+        assert_eq!(result.unwrap().to_hex(), secret, "{}", description);
+
+        let key =
+            DescriptorSecretKey::from_slip39(Network::Bitcoin, mnemonics, Some("TREZOR".into()))
+                .unwrap();
+        let expected =
+            DescriptorSecretKey::from_seed(Network::Bitcoin, Vec::from_hex(&secret).unwrap())
+                .unwrap();
+        assert_eq!(key.as_string(), expected.as_string(), "{}", description);
+    }
+}
+
+#[test]
+fn test_official_vectors() {
+    let vectors: Vec<(String, Vec<String>, String)> = serde_json::from_str(VECTORS).unwrap();
+    assert_eq!(vectors.len(), 43);
+    check_vectors(vectors);
+}
+
+#[test]
+fn test_cross_checked_vectors() {
+    let vectors: Vec<(String, Vec<String>, String)> =
+        serde_json::from_str(CROSS_CHECKED_VECTORS).unwrap();
+    assert_eq!(vectors.len(), 2);
+    check_vectors(vectors);
+}
+
+#[test]
+fn test_split_and_combine_groups() {
+    let secret = Vec::<u8>::from_hex("bb54aac4b89dc868ba37d9cc21b2cece").unwrap();
+    let passphrase = Some("TREZOR".to_string());
+    let slip39 = Slip39::new(2, vec![group(2, 3), group(1, 1), group(3, 5)])
+        .unwrap()
+        .with_iteration_exponent(0);
+    let groups = slip39.split(secret.clone(), passphrase.clone()).unwrap();
+    assert_eq!(
+        groups.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![3, 1, 5]
+    );
+    assert!(groups
+        .iter()
+        .flatten()
+        .all(|share| share.split(' ').count() == 20));
+
+    let combine = |shares: Vec<&String>, passphrase: Option<String>| {
+        Slip39::combine(shares.into_iter().cloned().collect(), passphrase)
+    };
+    for shares in [
+        vec![&groups[0][2], &groups[1][0], &groups[0][0]],
+        vec![
+            &groups[2][4],
+            &groups[0][1],
+            &groups[2][0],
+            &groups[0][2],
+            &groups[2][2],
+        ],
+        // The incomplete third group is ignored.
+        vec![
+            &groups[2][1],
+            &groups[0][0],
+            &groups[2][3],
+            &groups[1][0],
+            &groups[0][1],
+        ],
+        // Giving a share twice does no harm.
+        vec![&groups[1][0], &groups[0][0], &groups[0][0], &groups[0][1]],
+    ] {
+        assert_eq!(combine(shares, passphrase.clone()).unwrap(), secret);
+    }
+
+    for shares in [
+        vec![&groups[0][0], &groups[1][0]],
+        vec![&groups[0][0], &groups[0][1]],
+        vec![&groups[2][0], &groups[2][1], &groups[1][0]],
+    ] {
+        assert!(matches!(
+            combine(shares, passphrase.clone()),
+            Err(Error::InvalidShares(_))
+        ));
+    }
+
+    // A wrong passphrase gives another secret.
+    let shares = vec![&groups[1][0], &groups[0][0], &groups[0][1]];
+    assert_ne!(combine(shares, None).unwrap(), secret);
+}
+
+#[test]
+fn test_non_extendable_shares() {
+    let secret = vec![7u8; 32];
+    let groups = Slip39::new(1, vec![group(2, 3)])
+        .unwrap()
+        .with_iteration_exponent(0)
+        .with_extendable(false)
+        .split(secret.clone(), None)
+        .unwrap();
+    assert!(groups[0].iter().all(|share| share.split(' ').count() == 33));
+    assert_eq!(
+        Slip39::combine(vec![groups[0][2].clone(), groups[0][1].clone()], None).unwrap(),
+        secret
+    );
+
+    // Shares from different splits never combine.
+    let other = Slip39::new(1, vec![group(2, 3)])
+        .unwrap()
+        .with_iteration_exponent(0)
+        .split(secret, None)
+        .unwrap();
+    assert!(matches!(
+        Slip39::combine(vec![groups[0][0].clone(), other[0][1].clone()], None),
+        Err(Error::InvalidShares(_))
+    ));
+}
+
+#[test]
+fn test_keys_from_shares() {
+    let seed = Vec::<u8>::from_hex(TREZOR_SEED).unwrap();
+    let groups = Slip39::new(1, vec![group(1, 1)])
+        .unwrap()
+        .with_iteration_exponent(0)
+        .split(seed, None)
+        .unwrap();
+    let share = groups[0].clone();
+    assert_eq!(share[0].split(' ').count(), 59);
+
+    let expected = format!("{}/*", TREZOR_XPRV);
+    let key = DescriptorSecretKey::from_slip39(Network::Bitcoin, share.clone(), None).unwrap();
+    assert_eq!(key.as_string(), expected);
+    let key = DescriptorKey::from_slip39(Network::Bitcoin, share, None).unwrap();
+    assert_eq!(key.as_string(), expected);
+}
+
+#[test]
+fn test_invalid_parameters() {
+    assert!(Slip39::new(3, vec![group(1, 1), group(2, 2)]).is_err());
+    assert!(Slip39::new(0, vec![group(1, 1)]).is_err());
+    assert!(Slip39::new(1, vec![]).is_err());
+    assert!(Slip39::new(1, vec![group(1, 2)]).is_err());
+    assert!(Slip39::new(1, vec![group(3, 2)]).is_err());
+    assert!(Slip39::new(1, vec![group(2, 17)]).is_err());
+
+    let slip39 = Slip39::new(1, vec![group(1, 1)])
+        .unwrap()
+        .with_iteration_exponent(0);
+    for secret in [vec![0u8; 14], vec![0u8; 17]] {
+        assert!(matches!(
+            slip39.split(secret, None),
+            Err(Error::InvalidShares(_))
+        ));
+    }
+    assert!(matches!(
+        slip39.split(vec![0u8; 16], Some("pässphrase".to_string())),
+        Err(Error::InvalidShares(_))
+    ));
+    assert!(slip39
+        .clone()
+        .with_iteration_exponent(16)
+        .split(vec![0u8; 16], None)
+        .is_err());
+
+    let share = slip39.split(vec![0u8; 16], None).unwrap()[0][0].clone();
+    let typo = share.replacen(' ', "x ", 1);
+    assert!(matches!(
+        Slip39::combine(vec![typo], None),
+        Err(Error::InvalidShares(_))
+    ));
+    assert!(matches!(
+        Slip39::combine(vec![], None),
+        Err(Error::InvalidShares(_))
+    ));
+}
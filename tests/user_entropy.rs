@@ -0,0 +1,158 @@
+use bdk::bitcoin::hashes::hex::{FromHex, ToHex};
+use bdk::bitcoin::hashes::{sha256, Hash};
+use extended_keys_derive_rust::{EntropySource, Error, Language, UserEntropy, WordCount};
+
+#[test]
+fn test_dice_rolls_are_hashed() {
+    let rolls = "1".repeat(50);
+    let entropy = UserEntropy::new(EntropySource::D6, rolls.clone()).unwrap();
+    assert_eq!(entropy.canonical(), rolls);
+    assert_eq!(entropy.bits(), 129);
+
+    // `printf 1111...1 | sha256sum` with fifty ones.
+    let mnemonic = entropy
+        .to_mnemonic(Language::English, WordCount::Words12, None)
+        .unwrap();
+    assert_eq!(
+        mnemonic.entropy().to_hex(),
+        "3dac51a65ec9fcfc409a1b5f1defe92b"
+    );
+    assert_eq!(
+        mnemonic.as_string(),
+        "diet glad hat rural panther lawsuit act drop gallery urge where fit"
+    );
+}
+
+#[test]
+fn test_canonical_forms() {
+    for (source, input, canonical, bits) in [
+        (EntropySource::D6, "16 62\n5", "16625", 12),
+        (EntropySource::D20, " 20  01 7 ", "20 1 7", 12),
+        (EntropySource::Cards, "as 10h Td 2C", "AS TH TD 2C", 22),
+        (EntropySource::CoinFlips, "h T ht", "HTHT", 4),
+        (EntropySource::Hex, "DE ad", "dead", 16),
+    ] {
+        let entropy = UserEntropy::new(source, input.to_string()).unwrap();
+        assert_eq!(entropy.source(), source);
+        assert_eq!(entropy.canonical(), canonical);
+        assert_eq!(entropy.bits(), bits);
+    }
+
+    let first = UserEntropy::new(EntropySource::Cards, "as 10h ".repeat(12)).unwrap();
+    let second = UserEntropy::new(
+        EntropySource::Cards,
+        format!("AS TH{}", " AS TH".repeat(11)),
+    )
+    .unwrap();
+    assert_eq!(
+        first.entropy(WordCount::Words12).unwrap(),
+        second.entropy(WordCount::Words12).unwrap()
+    );
+    assert_eq!(
+        first.entropy(WordCount::Words12).unwrap(),
+        sha256::Hash::hash(first.canonical().as_bytes())[..16].to_vec()
+    );
+}
+
+#[test]
+fn test_bit_counts() {
+    // 99 d6 rolls hold 255.9 bits and 100 hold 258.5.
+    let rolls = UserEntropy::new(EntropySource::D6, "3".repeat(99)).unwrap();
+    assert!(matches!(
+        rolls.entropy(WordCount::Words24),
+        Err(Error::InsufficientEntropy {
+            required: 256,
+            provided: 255
+        })
+    ));
+    assert!(rolls.entropy(WordCount::Words21).is_ok());
+    let rolls = UserEntropy::new(EntropySource::D6, "3".repeat(100)).unwrap();
+    assert_eq!(rolls.entropy(WordCount::Words24).unwrap().len(), 32);
+
+    let flips = UserEntropy::new(EntropySource::CoinFlips, "HT".repeat(80)).unwrap();
+    assert_eq!(flips.entropy(WordCount::Words15).unwrap().len(), 20);
+    assert!(matches!(
+        flips.entropy(WordCount::Words18),
+        Err(Error::InsufficientEntropy {
+            required: 192,
+            provided: 160
+        })
+    ));
+}
+
+#[test]
+fn test_hex_entropy_is_used_as_is() {
+    let entropy = UserEntropy::new(EntropySource::Hex, "00".repeat(16)).unwrap();
+    let mnemonic = entropy
+        .to_mnemonic(Language::English, WordCount::Words12, None)
+        .unwrap();
+    assert_eq!(
+        mnemonic.as_string(),
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+    );
+
+    let mnemonic = entropy
+        .to_mnemonic(Language::Spanish, WordCount::Words12, None)
+        .unwrap();
+    assert_eq!(mnemonic.language(), Language::Spanish);
+    assert_eq!(mnemonic.entropy(), vec![0u8; 16]);
+
+    assert!(matches!(
+        entropy.entropy(WordCount::Words24),
+        Err(Error::InsufficientEntropy { .. })
+    ));
+    let long = UserEntropy::new(EntropySource::Hex, "00".repeat(20)).unwrap();
+    assert!(matches!(
+        long.entropy(WordCount::Words12),
+        Err(Error::InvalidEntropy(_))
+    ));
+}
+
+#[test]
+fn test_mixing() {
+    let entropy = UserEntropy::new(EntropySource::Hex, "0f".repeat(16)).unwrap();
+    let mix = Vec::<u8>::from_hex(&"ff".repeat(16)).unwrap();
+    let mnemonic = entropy
+        .to_mnemonic(Language::English, WordCount::Words12, Some(mix))
+        .unwrap();
+    assert_eq!(mnemonic.entropy(), vec![0xf0u8; 16]);
+
+    let os = UserEntropy::os_randomness(WordCount::Words12);
+    assert_eq!(os.len(), 16);
+    let mixed = entropy
+        .to_mnemonic(Language::English, WordCount::Words12, Some(os.clone()))
+        .unwrap();
+    let expected: Vec<u8> = os.iter().map(|byte| byte ^ 0x0f).collect();
+    assert_eq!(mixed.entropy(), expected);
+
+    assert!(matches!(
+        entropy.to_mnemonic(Language::English, WordCount::Words12, Some(vec![0u8; 4])),
+        Err(Error::InvalidEntropy(_))
+    ));
+}
+
+#[test]
+fn test_invalid_symbols() {
+    for (source, input) in [
+        (EntropySource::D6, "1234567"),
+        (EntropySource::D6, "0"),
+        (EntropySource::D20, "21"),
+        (EntropySource::D20, "0"),
+        (EntropySource::Cards, "1S"),
+        (EntropySource::Cards, "AX"),
+        (EntropySource::Cards, "♠A"),
+        (EntropySource::CoinFlips, "HTX"),
+        (EntropySource::Hex, "0g"),
+        (EntropySource::Hex, "abc"),
+    ] {
+        assert!(
+            matches!(
+                UserEntropy::new(source, input.to_string()),
+                Err(Error::InvalidEntropy(_))
+            ),
+            "{:?} {}",
+            source,
+            input
+        );
+    }
+}
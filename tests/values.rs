@@ -0,0 +1,44 @@
+mod common;
+
+use std::collections::HashSet;
+
+use extended_keys_derive_rust::{
+    DerivationPath, DescriptorKey, DescriptorPublicKey, DescriptorSecretKey, Mnemonic, Network,
+};
+
+use common::{mnemonic, path};
+
+fn receive_dpk() -> DescriptorPublicKey {
+    let mnemonic = mnemonic();
+    DescriptorSecretKey::new(Network::Testnet, &mnemonic, None)
+        .unwrap()
+        .derive(&path("m/84h/1h/0h"))
+        .unwrap()
+        .as_public()
+        .unwrap()
+        .extend(&path("m/0"))
+        .unwrap()
+}
+
+#[test]
+fn test_keys_are_shareable_values() {
+    fn assert_value<T: Send + Sync + Clone + Eq + std::hash::Hash>() {}
+    assert_value::<DescriptorSecretKey>();
+    assert_value::<DescriptorPublicKey>();
+    assert_value::<DerivationPath>();
+    assert_value::<DescriptorKey>();
+    assert_value::<Mnemonic>();
+
+    let receive = receive_dpk();
+    let keys: HashSet<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|index| {
+                let receive = &receive;
+                scope.spawn(move || receive.at_index(index % 2).unwrap())
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(keys.len(), 2);
+    assert_eq!(receive.clone(), receive);
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use extended_keys_derive_rust::{
+    DescriptorKey, DescriptorPublicKey, DescriptorSecretKey, Error, Network, Wildcard,
+};
+
+use common::{master_dsk, mnemonic, path};
+
+const WIF: &str = "cMpMxK92W1DjqDvWV3pMn4xLwAuQJhNF3MFqkEHUQRPQofUJku8R";
+
+#[test]
+fn test_secret_key_wildcard() {
+    let master = master_dsk();
+    assert_eq!(master.wildcard(), Wildcard::Unhardened);
+
+    let hardened = master
+        .extend(&path("m/0h"))
+        .unwrap()
+        .with_wildcard(Wildcard::Hardened)
+        .unwrap();
+    assert_eq!(hardened.wildcard(), Wildcard::Hardened);
+    assert!(hardened.as_string().ends_with("/0'/*h"));
+
+    let derived = hardened.derive(&path("m/1h")).unwrap();
+    assert_eq!(derived.wildcard(), Wildcard::Hardened);
+    let extended = hardened.extend(&path("m/1")).unwrap();
+    assert_eq!(extended.wildcard(), Wildcard::Hardened);
+
+    let fixed = master.with_wildcard(Wildcard::None).unwrap();
+    assert_eq!(fixed.wildcard(), Wildcard::None);
+    assert!(!fixed.as_string().ends_with('*'));
+    assert_eq!(fixed.as_public().unwrap().wildcard(), Wildcard::None);
+    assert_eq!(
+        fixed.derive(&path("m/0")).unwrap().wildcard(),
+        Wildcard::None
+    );
+
+    // hardened wildcards have no public counterpart
+    assert!(matches!(
+        hardened.as_public(),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+
+    let parsed = DescriptorSecretKey::from_string(Network::Testnet, hardened.as_string()).unwrap();
+    assert_eq!(parsed.wildcard(), Wildcard::Hardened);
+}
+
+#[test]
+fn test_public_key_wildcard() {
+    let master = master_dsk().as_public().unwrap();
+    assert_eq!(master.wildcard(), Wildcard::Unhardened);
+
+    let fixed = master.with_wildcard(Wildcard::None).unwrap();
+    assert_eq!(fixed.wildcard(), Wildcard::None);
+    assert_eq!(
+        fixed.extend(&path("m/0")).unwrap().wildcard(),
+        Wildcard::None
+    );
+    assert_eq!(
+        fixed.derive(&path("m/0")).unwrap().wildcard(),
+        Wildcard::None
+    );
+
+    assert!(matches!(
+        master.with_wildcard(Wildcard::Hardened),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+
+    let parsed = DescriptorPublicKey::from_string(Network::Testnet, fixed.as_string()).unwrap();
+    assert_eq!(parsed.wildcard(), Wildcard::None);
+}
+
+#[test]
+fn test_single_key_wildcard() {
+    let single = DescriptorSecretKey::from_string(Network::Testnet, WIF.to_string()).unwrap();
+    assert_eq!(single.wildcard(), Wildcard::None);
+    assert!(single.with_wildcard(Wildcard::None).is_ok());
+    assert!(matches!(
+        single.with_wildcard(Wildcard::Unhardened),
+        Err(Error::SingleKeyDerivation)
+    ));
+    assert_eq!(single.as_public().unwrap().wildcard(), Wildcard::None);
+}
+
+#[test]
+fn test_descriptor_key_wildcard() {
+    let master = DescriptorKey::new(Network::Testnet, &mnemonic(), None).unwrap();
+    assert_eq!(master.wildcard(), Wildcard::Unhardened);
+
+    let hardened = master.with_wildcard(Wildcard::Hardened).unwrap();
+    let derived = hardened
+        .derive(Some(&path("m/84h/1h/0h")), Some(&path("m/0h")))
+        .unwrap();
+    assert_eq!(derived.wildcard(), Wildcard::Hardened);
+    assert!(derived.as_string().ends_with("/0'/*h"));
+    assert!(matches!(
+        derived.as_public(),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+
+    let fixed = master.with_wildcard(Wildcard::None).unwrap();
+    let public = fixed
+        .derive(Some(&path("m/84h/1h/0h")), None)
+        .unwrap()
+        .as_public()
+        .unwrap();
+    assert_eq!(public.wildcard(), Wildcard::None);
+    assert!(matches!(
+        public.with_wildcard(Wildcard::Hardened),
+        Err(Error::HardenedDerivationFromPublic)
+    ));
+    assert_eq!(
+        public
+            .with_wildcard(Wildcard::Unhardened)
+            .unwrap()
+            .wildcard(),
+        Wildcard::Unhardened
+    );
+}